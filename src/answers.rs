@@ -0,0 +1,202 @@
+use std::fmt;
+use std::io::ErrorKind;
+
+use crate::error::{Error, Result};
+use crate::runner::Report;
+use crate::solution::Part;
+use crate::utils::{day_file, open_file, parse_key_values};
+
+/// Accepted answers for one day, read from `inputs/<day>/answers.toml`.
+///
+/// Holds `part1` and `part2` keys, see [`parse_key_values`] for the syntax.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> std::result::Result<Self, String> {
+        let mut answers = Self::default();
+
+        for (index, key, value) in parse_key_values(input)? {
+            match key {
+                "part1" => answers.part1 = Some(value.to_string()),
+                "part2" => answers.part2 = Some(value.to_string()),
+                key => return Err(format!("line {}: unknown key `{}`", index + 1, key)),
+            }
+        }
+
+        Ok(answers)
+    }
+
+    /// Missing files are not an error, the day simply has no recorded answers.
+    pub fn load(day: u8) -> std::result::Result<Self, String> {
+        let path = day_file(day, "answers.toml");
+        match open_file(&path) {
+            Ok(contents) => {
+                Self::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Mismatch { expected: String, actual: String },
+    Failed(String),
+    Unrecorded(String),
+    Unsolved,
+}
+
+impl Verdict {
+    pub fn new(expected: Option<&str>, answer: &Result<String>) -> Self {
+        match (expected, answer) {
+            // a recorded answer whose solver went missing must not pass
+            (None, Err(Error::Unsolved { .. })) => Self::Unsolved,
+            (Some(expected), Ok(actual)) if expected == actual => Self::Correct,
+            (Some(expected), Ok(actual)) => Self::Mismatch {
+                expected: expected.to_string(),
+                actual: actual.clone(),
+            },
+            (_, Err(error)) => Self::Failed(error.to_string()),
+            (None, Ok(actual)) => Self::Unrecorded(actual.clone()),
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Mismatch { .. } | Self::Failed(_))
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "ok"),
+            Self::Mismatch { expected, actual } => {
+                write!(f, "MISMATCH: expected {}, got {}", expected, actual)
+            }
+            Self::Failed(message) => write!(f, "FAILED: {}", message),
+            Self::Unrecorded(actual) => write!(f, "no recorded answer, got {}", actual),
+            Self::Unsolved => write!(f, "not implemented"),
+        }
+    }
+}
+
+/// Compares every report with the recorded answers of its day.
+pub fn verify(reports: &[Report]) -> Vec<(&Report, Verdict)> {
+    reports
+        .iter()
+        .map(|report| {
+            let verdict = match Answers::load(report.day) {
+                Ok(answers) => Verdict::new(answers.get(report.part), &report.answer),
+                Err(message) => Verdict::Failed(message),
+            };
+            (report, verdict)
+        })
+        .collect()
+}
+
+pub fn summary(verdicts: &[(&Report, Verdict)]) -> String {
+    let mut table = vec!["Day  Part  Verdict".to_string()];
+    table.extend(
+        verdicts
+            .iter()
+            .map(|(report, verdict)| format!("{:>3}  {:>4}  {}", report.day, report.part, verdict)),
+    );
+
+    let failures = verdicts
+        .iter()
+        .filter(|(_, verdict)| verdict.is_failure())
+        .count();
+    table.push(format!("{} failure(s)", failures));
+
+    table.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let input = "# day 8
+part1 = 13019
+part2 = \"13524038372771\"
+";
+
+        let answers = Answers::parse(input).unwrap();
+
+        assert_eq!(answers.get(Part::One), Some("13019"));
+        assert_eq!(answers.get(Part::Two), Some("13524038372771"));
+    }
+
+    #[test]
+    fn parse_partial_answers() {
+        let answers = Answers::parse("part1 = \"abc\"").unwrap();
+
+        assert_eq!(answers.get(Part::One), Some("abc"));
+        assert_eq!(answers.get(Part::Two), None);
+    }
+
+    #[test]
+    fn parse_invalid_answers() {
+        assert!(Answers::parse("part1").is_err());
+        assert!(Answers::parse("part3 = 1").is_err());
+        assert!(Answers::parse("part1 = \"1").is_err());
+        assert!(Answers::parse("part1 = one").is_err());
+    }
+
+    #[test]
+    fn verdicts() {
+        let answer = Ok("42".to_string());
+
+        assert_eq!(Verdict::new(Some("42"), &answer), Verdict::Correct);
+        assert_eq!(
+            Verdict::new(Some("41"), &answer),
+            Verdict::Mismatch {
+                expected: "41".to_string(),
+                actual: "42".to_string()
+            }
+        );
+        assert_eq!(
+            Verdict::new(None, &answer),
+            Verdict::Unrecorded("42".to_string())
+        );
+        assert_eq!(
+            Verdict::new(
+                None,
+                &Err(Error::Unsolved {
+                    day: 9,
+                    part: Part::Two
+                })
+            ),
+            Verdict::Unsolved
+        );
+        let lost = Verdict::new(
+            Some("42"),
+            &Err(Error::Unsolved {
+                day: 9,
+                part: Part::Two,
+            }),
+        );
+        assert_eq!(
+            lost,
+            Verdict::Failed("day 9 part 2 is not implemented".to_string())
+        );
+        assert!(lost.is_failure());
+        let error = Err(Error::NoSolution("boom".to_string()));
+        assert!(Verdict::new(Some("42"), &error).is_failure());
+        assert!(Verdict::new(None, &error).is_failure());
+    }
+}
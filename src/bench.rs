@@ -0,0 +1,483 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::hint::black_box;
+use std::iter::Peekable;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::runner::Source;
+use crate::solution::{self, Part, Puzzle};
+use crate::utils::open_file;
+
+/// A median more than 10% slower than the baseline counts as a regression.
+const REGRESSION_THRESHOLD: f64 = 0.10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Every implemented day when unset.
+    pub day: Option<u8>,
+    /// Both parts when unset.
+    pub part: Option<Part>,
+    pub runs: usize,
+    pub warmup: usize,
+    pub source: Source,
+    pub json: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            day: None,
+            part: None,
+            runs: 100,
+            warmup: 3,
+            source: Source::Input,
+            json: None,
+            baseline: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Percentiles use the nearest rank, so every value is an actual sample.
+    pub fn new(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort();
+        let rank = |percentile: usize| {
+            let index = (samples.len() * percentile).div_ceil(100);
+            samples[index.saturating_sub(1)]
+        };
+
+        Some(Self {
+            runs: samples.len(),
+            min: *samples.first()?,
+            median: rank(50),
+            p95: rank(95),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Measurement {
+    pub day: u8,
+    pub part: Part,
+    pub stats: Result<Stats>,
+}
+
+/// Times `puzzle` like the `all` command does: parsing and solving, without
+/// reading the input.
+fn measure(puzzle: &dyn Puzzle, part: Part, input: &str, options: &Options) -> Result<Stats> {
+    for _ in 0..options.warmup {
+        black_box(puzzle.solve(part, black_box(input))?);
+    }
+
+    let mut samples = Vec::with_capacity(options.runs);
+    for _ in 0..options.runs {
+        let start = Instant::now();
+        let answer = puzzle.solve(part, black_box(input));
+        samples.push(start.elapsed());
+        black_box(answer?);
+    }
+
+    Stats::new(samples).ok_or_else(|| Error::NoSolution("at least one run is needed".to_string()))
+}
+
+pub fn run(options: &Options) -> Result<Vec<Measurement>> {
+    let puzzles = match options.day {
+        Some(day) => vec![solution::find(day).ok_or(Error::UnknownDay(day))?],
+        None => solution::PUZZLES.to_vec(),
+    };
+    let parts = match options.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+
+    let mut measurements = vec![];
+    for puzzle in puzzles {
+        let day = puzzle.day();
+        let input = options.source.read(day);
+        for &part in &parts {
+            let stats = match &input {
+                Ok(input) => measure(puzzle, part, input, options),
+                Err(error) => Err(error.clone()),
+            };
+            measurements.push(Measurement { day, part, stats });
+        }
+    }
+
+    Ok(measurements)
+}
+
+/// Timings of an earlier run, as written by [`to_json`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Baseline(BTreeMap<(u8, Part), Stats>);
+
+impl Baseline {
+    pub fn parse(input: &str) -> std::result::Result<Self, String> {
+        let mut baseline = Self::default();
+
+        for object in parse_json(input)? {
+            let field = |key: &str| {
+                object
+                    .get(key)
+                    .copied()
+                    .ok_or_else(|| format!("missing `{}`", key))
+            };
+            let day = u8::try_from(field("day")?).map_err(|_| "invalid `day`".to_string())?;
+            let part = u8::try_from(field("part")?)
+                .ok()
+                .and_then(Part::from_number)
+                .ok_or("invalid `part`")?;
+            let stats = Stats {
+                runs: field("runs")? as usize,
+                min: Duration::from_nanos(field("min_ns")?),
+                median: Duration::from_nanos(field("median_ns")?),
+                p95: Duration::from_nanos(field("p95_ns")?),
+            };
+            baseline.0.insert((day, part), stats);
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn load(path: &PathBuf) -> std::result::Result<Self, String> {
+        let contents = open_file(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Stats> {
+        self.0.get(&(day, part))
+    }
+}
+
+/// Relative change of the median, `0.25` meaning 25% slower.
+fn change(stats: &Stats, baseline: &Stats) -> f64 {
+    let before = baseline.median.as_secs_f64();
+    if before == 0.0 {
+        return 0.0;
+    }
+    (stats.median.as_secs_f64() - before) / before
+}
+
+pub fn is_regression(stats: &Stats, baseline: &Stats) -> bool {
+    change(stats, baseline) > REGRESSION_THRESHOLD
+}
+
+/// Measurements whose median regressed against `baseline`.
+pub fn regressions<'a>(
+    measurements: &'a [Measurement],
+    baseline: &Baseline,
+) -> Vec<&'a Measurement> {
+    measurements
+        .iter()
+        .filter(|measurement| {
+            match (
+                &measurement.stats,
+                baseline.get(measurement.day, measurement.part),
+            ) {
+                (Ok(stats), Some(before)) => is_regression(stats, before),
+                _ => false,
+            }
+        })
+        .collect()
+}
+
+/// One object per solved part; failed parts are left out.
+pub fn to_json(measurements: &[Measurement]) -> String {
+    let objects: Vec<_> = measurements
+        .iter()
+        .filter_map(|measurement| {
+            let stats = measurement.stats.as_ref().ok()?;
+            Some(format!(
+                "  {{\"day\": {}, \"part\": {}, \"runs\": {}, \"min_ns\": {}, \"median_ns\": {}, \"p95_ns\": {}}}",
+                measurement.day,
+                measurement.part,
+                stats.runs,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.p95.as_nanos()
+            ))
+        })
+        .collect();
+
+    if objects.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
+
+/// Reads the subset of JSON [`to_json`] writes: an array of flat objects
+/// whose values are unsigned integers.
+fn parse_json(input: &str) -> std::result::Result<Vec<BTreeMap<String, u64>>, String> {
+    let mut chars = input.chars().filter(|c| !c.is_whitespace()).peekable();
+
+    let mut objects = vec![];
+    expect(&mut chars, '[')?;
+    if chars.next_if_eq(&']').is_none() {
+        loop {
+            objects.push(parse_object(&mut chars)?);
+            match chars.next() {
+                Some(',') => continue,
+                Some(']') => break,
+                found => return Err(unexpected("`,` or `]`", found)),
+            }
+        }
+    }
+
+    match chars.next() {
+        None => Ok(objects),
+        found => Err(unexpected("the end of the file", found)),
+    }
+}
+
+fn parse_object<I>(chars: &mut Peekable<I>) -> std::result::Result<BTreeMap<String, u64>, String>
+where
+    I: Iterator<Item = char>,
+{
+    let mut object = BTreeMap::new();
+    expect(chars, '{')?;
+    if chars.next_if_eq(&'}').is_some() {
+        return Ok(object);
+    }
+
+    loop {
+        expect(chars, '"')?;
+        let key: String = chars.by_ref().take_while(|&c| c != '"').collect();
+        expect(chars, ':')?;
+        let mut value = String::new();
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            value.push(digit);
+        }
+        let value = value
+            .parse()
+            .map_err(|_| format!("invalid value for `{}`", key))?;
+        object.insert(key, value);
+
+        match chars.next() {
+            Some(',') => continue,
+            Some('}') => return Ok(object),
+            found => return Err(unexpected("`,` or `}`", found)),
+        }
+    }
+}
+
+fn expect<I>(chars: &mut Peekable<I>, expected: char) -> std::result::Result<(), String>
+where
+    I: Iterator<Item = char>,
+{
+    match chars.next() {
+        Some(c) if c == expected => Ok(()),
+        found => Err(unexpected(&format!("`{}`", expected), found)),
+    }
+}
+
+fn unexpected(expected: &str, found: Option<char>) -> String {
+    match found {
+        Some(c) => format!("expected {}, found `{}`", expected, c),
+        None => format!("expected {}, found the end of the file", expected),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+/// Lays the measurements out as a table. With a baseline, the change of the
+/// median is added and regressions are flagged.
+pub fn summary(measurements: &[Measurement], baseline: Option<&Baseline>) -> String {
+    let mut rows = vec![];
+    for measurement in measurements {
+        let mut row = vec![
+            format!("{:>3}", measurement.day),
+            format!("{:>4}", measurement.part),
+        ];
+        match &measurement.stats {
+            Ok(stats) => {
+                row.extend([stats.min, stats.median, stats.p95].map(format_duration));
+                let before = baseline.and_then(|b| b.get(measurement.day, measurement.part));
+                match before {
+                    Some(before) => {
+                        row.push(format_duration(before.median));
+                        let mut change = format!("{:+.1}%", change(stats, before) * 100.0);
+                        if is_regression(stats, before) {
+                            change.push_str(" REGRESSION");
+                        }
+                        row.push(change);
+                    }
+                    None if baseline.is_some() => row.extend(["-".to_string(), "-".to_string()]),
+                    None => (),
+                }
+            }
+            Err(error) => {
+                let message = error.to_string();
+                row.push(format!(
+                    "- ({})",
+                    message.lines().next().unwrap_or_default()
+                ));
+            }
+        }
+        rows.push(row);
+    }
+
+    let mut header = vec!["Day", "Part", "Min", "Median", "p95"];
+    if baseline.is_some() {
+        header.extend(["Baseline", "Change"]);
+    }
+    let mut widths: Vec<_> = header.iter().map(|title| title.len()).collect();
+    for row in rows.iter().filter(|row| row.len() == header.len()) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    let header: Vec<_> = header.iter().map(|title| title.to_string()).collect();
+    for row in std::iter::once(&header).chain(&rows) {
+        let mut line = String::new();
+        for (index, cell) in row.iter().enumerate() {
+            let width = if index + 1 < row.len() {
+                widths[index]
+            } else {
+                0
+            };
+            if index < 2 {
+                write!(line, "{:>width$}  ", cell).unwrap();
+            } else {
+                write!(line, "{:<width$}  ", cell).unwrap();
+            }
+        }
+        writeln!(table, "{}", line.trim_end()).unwrap();
+    }
+    table.pop();
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(median_ms: u64) -> Stats {
+        Stats {
+            runs: 10,
+            min: Duration::from_millis(1),
+            median: Duration::from_millis(median_ms),
+            p95: Duration::from_millis(20),
+        }
+    }
+
+    #[test]
+    fn stats_percentiles() {
+        let samples = (1..=100).rev().map(Duration::from_millis).collect();
+
+        let stats = Stats::new(samples).unwrap();
+
+        assert_eq!(stats.runs, 100);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(50));
+        assert_eq!(stats.p95, Duration::from_millis(95));
+
+        let single = Stats::new(vec![Duration::from_millis(7)]).unwrap();
+        assert_eq!(single.median, Duration::from_millis(7));
+        assert_eq!(single.p95, Duration::from_millis(7));
+        assert!(Stats::new(vec![]).is_none());
+    }
+
+    #[test]
+    fn missing_input_is_an_io_error() {
+        let options = Options {
+            day: Some(9),
+            source: Source::Path(PathBuf::from("/nonexistent/day9.txt")),
+            ..Options::default()
+        };
+
+        let measurements = run(&options).unwrap();
+
+        assert_eq!(measurements.len(), 2);
+        for measurement in measurements {
+            assert!(matches!(measurement.stats, Err(Error::Io { .. })));
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let measurements = vec![
+            Measurement {
+                day: 14,
+                part: Part::One,
+                stats: Ok(stats(5)),
+            },
+            Measurement {
+                day: 14,
+                part: Part::Two,
+                stats: Err(Error::Unsolved {
+                    day: 14,
+                    part: Part::Two,
+                }),
+            },
+        ];
+
+        let json = to_json(&measurements);
+        let baseline = Baseline::parse(&json).unwrap();
+
+        assert_eq!(
+            json,
+            "[
+  {\"day\": 14, \"part\": 1, \"runs\": 10, \"min_ns\": 1000000, \"median_ns\": 5000000, \"p95_ns\": 20000000}
+]
+"
+        );
+        assert_eq!(baseline.get(14, Part::One), Some(&stats(5)));
+        assert_eq!(baseline.get(14, Part::Two), None);
+        assert_eq!(Baseline::parse("[]"), Ok(Baseline::default()));
+    }
+
+    #[test]
+    fn parse_invalid_baseline() {
+        assert!(Baseline::parse("").is_err());
+        assert!(Baseline::parse("[{\"day\": 14}]").is_err());
+        assert!(Baseline::parse("[{\"day\": 14, \"part\": 3, \"runs\": 1, \"min_ns\": 1, \"median_ns\": 1, \"p95_ns\": 1}]").is_err());
+        assert!(Baseline::parse("[{\"day\": -1}]").is_err());
+        assert!(Baseline::parse("[] []").is_err());
+    }
+
+    #[test]
+    fn compare_with_baseline() {
+        let mut baseline = Baseline::default();
+        baseline.0.insert((11, Part::One), stats(10));
+        baseline.0.insert((14, Part::One), stats(10));
+        let measurements = vec![
+            Measurement {
+                day: 11,
+                part: Part::One,
+                stats: Ok(stats(8)),
+            },
+            Measurement {
+                day: 14,
+                part: Part::One,
+                stats: Ok(stats(12)),
+            },
+        ];
+
+        let regressions = regressions(&measurements, &baseline);
+
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].day, 14);
+        assert_eq!(
+            summary(&measurements, Some(&baseline)),
+            "Day  Part  Min     Median   p95      Baseline  Change
+ 11     1  1.00ms  8.00ms   20.00ms  10.00ms   -20.0%
+ 14     1  1.00ms  12.00ms  20.00ms  10.00ms   +20.0% REGRESSION"
+        );
+    }
+}
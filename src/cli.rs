@@ -0,0 +1,532 @@
+use std::path::PathBuf;
+
+use crate::bench;
+use crate::day1;
+use crate::runner::Source;
+use crate::solution::Part;
+use crate::utils::image::Palette;
+
+pub const USAGE: &str = "usage: adventofcode2023 <command> [options]
+
+commands:
+  run --day <N> --part <1|2> [--render] [--image <PATH>]
+                                solve one part of a day and print the answer
+  all                           solve every day and print a timing summary
+  verify                        check every answer against inputs/<N>/answers.toml
+  bench [--day <N>] [--part <1|2>]
+                                time the solvers and report min, median and p95
+  new-day <N>                   scaffold src/day<N>.rs and register it
+  fetch --day <N>               download the puzzle input unless it is cached
+  submit --day <N> --part <1|2>
+                                solve one part and post the answer
+  calibrate [--part <1|2>] [--lang <CODE>] [--vocab <PATH>] [--explain]
+                                sum the day 1 calibration values, optionally with
+                                another set of spelled out numbers
+  help                          show this message
+
+run options:
+  --render                      also draw the grid the part ends with, days 10,
+                                11 and 14 only, colours follow NO_COLOR
+  --image <PATH>                save the same grid as an .svg or .ppm image
+  --palette <COLOURS>           comma separated #rrggbb colours for --image,
+                                indexed as documented by each day
+  --cell-size <N>               pixels per cell for --image (default 8)
+
+bench options:
+  --runs <N>                    timed runs per part (default 100)
+  --warmup <N>                  untimed runs before measuring (default 3)
+  --json <PATH>                 write the timings as JSON, usable as a baseline
+  --baseline <PATH>             compare medians with an earlier --json file and
+                                fail when one is more than 10% slower
+
+calibrate options:
+  --part <1|2>                  1 only reads digits, 2 also reads words (default 2)
+  --lang <CODE>                 built in words, one of en, es, fr, de (default en)
+  --vocab <PATH>                read `word = digit` lines from PATH instead,
+                                for instance `zero = 0`
+  --explain                     print the numbers found on every line, the first
+                                and last ones and the resulting value
+
+input options (run, all, bench, calibrate):
+  --input <PATH>                read the puzzle input from PATH, `-` reads stdin
+  --example                     use inputs/<N>/test.txt instead of input.txt
+
+the inputs directory defaults to the one next to Cargo.toml and can be
+overridden with the AOC_INPUTS environment variable
+
+fetch and submit read `session`, `base_url` and `contact` from the config file at
+AOC_CONFIG or ~/.config/adventofcode2023/config.toml, the AOC_SESSION and
+AOC_BASE_URL environment variables take precedence";
+
+/// What `run` shows besides the answer.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct View {
+    pub render: bool,
+    pub image: Option<PathBuf>,
+    pub palette: Option<Palette>,
+    pub cell_size: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        day: u8,
+        part: Part,
+        source: Source,
+        view: View,
+    },
+    All {
+        source: Source,
+    },
+    Verify,
+    Bench(bench::Options),
+    NewDay {
+        day: u8,
+    },
+    Fetch {
+        day: u8,
+    },
+    Submit {
+        day: u8,
+        part: Part,
+    },
+    Calibrate(day1::Options),
+    Help,
+}
+
+impl Command {
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+
+        match args.next().as_deref() {
+            Some("run") => {
+                let mut day = None;
+                let mut part = None;
+                let mut source = Source::Input;
+                let mut view = View::default();
+
+                while let Some(flag) = args.next() {
+                    match flag.as_str() {
+                        "--day" | "-d" => day = Some(parse_value(&flag, args.next())?),
+                        "--part" | "-p" => part = Some(parse_value(&flag, args.next())?),
+                        "--render" => view.render = true,
+                        "--image" => view.image = Some(parse_path(&flag, args.next())?),
+                        "--palette" => {
+                            let colors = args
+                                .next()
+                                .ok_or_else(|| format!("missing value for `{}`", flag))?;
+                            view.palette = Some(Palette::parse(&colors)?)
+                        }
+                        "--cell-size" => match parse_count(&flag, args.next())? {
+                            0 => return Err("`--cell-size` must be at least 1".to_string()),
+                            size => view.cell_size = Some(size),
+                        },
+                        _ => source = parse_source(&flag, &mut args, source)?,
+                    }
+                }
+
+                let day = parse_day(day.ok_or("missing `--day`")?)?;
+                let part = parse_part(part.ok_or("missing `--part`")?)?;
+                if view.image.is_none() && (view.palette.is_some() || view.cell_size.is_some()) {
+                    return Err("`--palette` and `--cell-size` need `--image`".to_string());
+                }
+
+                Ok(Self::Run {
+                    day,
+                    part,
+                    source,
+                    view,
+                })
+            }
+            Some("all") => {
+                let mut source = Source::Input;
+
+                while let Some(flag) = args.next() {
+                    source = parse_source(&flag, &mut args, source)?;
+                }
+
+                match source {
+                    Source::Input | Source::Example => Ok(Self::All { source }),
+                    _ => Err("`all` only supports `--example`".to_string()),
+                }
+            }
+            Some("verify") => match args.next() {
+                Some(flag) => Err(format!("unknown option `{}`", flag)),
+                None => Ok(Self::Verify),
+            },
+            Some("bench") => {
+                let mut options = bench::Options::default();
+
+                while let Some(flag) = args.next() {
+                    match flag.as_str() {
+                        "--day" | "-d" => {
+                            options.day = Some(parse_day(parse_value(&flag, args.next())?)?)
+                        }
+                        "--part" | "-p" => {
+                            options.part = Some(parse_part(parse_value(&flag, args.next())?)?)
+                        }
+                        "--runs" | "-n" => options.runs = parse_count(&flag, args.next())?,
+                        "--warmup" => options.warmup = parse_count(&flag, args.next())?,
+                        "--json" => options.json = Some(parse_path(&flag, args.next())?),
+                        "--baseline" => options.baseline = Some(parse_path(&flag, args.next())?),
+                        _ => options.source = parse_source(&flag, &mut args, options.source)?,
+                    }
+                }
+
+                if options.runs == 0 {
+                    return Err("`--runs` must be at least 1".to_string());
+                }
+                match (&options.source, options.day) {
+                    (Source::Path(_) | Source::Stdin, None) => {
+                        Err("`--input` needs `--day`".to_string())
+                    }
+                    _ => Ok(Self::Bench(options)),
+                }
+            }
+            Some("new-day") => {
+                let day = parse_day(parse_value("new-day", args.next())?)?;
+                match args.next() {
+                    Some(flag) => Err(format!("unknown option `{}`", flag)),
+                    None => Ok(Self::NewDay { day }),
+                }
+            }
+            Some("fetch") => {
+                let mut day = None;
+
+                while let Some(flag) = args.next() {
+                    match flag.as_str() {
+                        "--day" | "-d" => day = Some(parse_value(&flag, args.next())?),
+                        _ => return Err(format!("unknown option `{}`", flag)),
+                    }
+                }
+
+                let day = parse_day(day.ok_or("missing `--day`")?)?;
+                Ok(Self::Fetch { day })
+            }
+            Some("submit") => {
+                let mut day = None;
+                let mut part = None;
+
+                while let Some(flag) = args.next() {
+                    match flag.as_str() {
+                        "--day" | "-d" => day = Some(parse_value(&flag, args.next())?),
+                        "--part" | "-p" => part = Some(parse_value(&flag, args.next())?),
+                        _ => return Err(format!("unknown option `{}`", flag)),
+                    }
+                }
+
+                let day = parse_day(day.ok_or("missing `--day`")?)?;
+                let part = parse_part(part.ok_or("missing `--part`")?)?;
+
+                Ok(Self::Submit { day, part })
+            }
+            Some("calibrate") => {
+                let mut options = day1::Options::default();
+
+                while let Some(flag) = args.next() {
+                    match flag.as_str() {
+                        "--lang" => {
+                            let code = args
+                                .next()
+                                .ok_or_else(|| format!("missing value for `{}`", flag))?;
+                            options.language = Some(code)
+                        }
+                        "--vocab" => options.vocab = Some(parse_path(&flag, args.next())?),
+                        "--part" | "-p" => {
+                            options.part = parse_part(parse_value(&flag, args.next())?)?
+                        }
+                        "--explain" => options.explain = true,
+                        _ => options.source = parse_source(&flag, &mut args, options.source)?,
+                    }
+                }
+
+                if options.language.is_some() && options.vocab.is_some() {
+                    return Err("`--lang` and `--vocab` cannot be combined".to_string());
+                }
+                if options.part == Part::One
+                    && (options.language.is_some() || options.vocab.is_some())
+                {
+                    return Err("part 1 only reads digits, drop `--lang` and `--vocab`".to_string());
+                }
+                Ok(Self::Calibrate(options))
+            }
+            Some("help" | "--help" | "-h") | None => Ok(Self::Help),
+            Some(command) => Err(format!("unknown command `{}`", command)),
+        }
+    }
+}
+
+fn parse_value(flag: &str, value: Option<String>) -> Result<u8, String> {
+    let value = value.ok_or_else(|| format!("missing value for `{}`", flag))?;
+    value
+        .parse::<u8>()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, flag))
+}
+
+fn parse_count(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("missing value for `{}`", flag))?;
+    value
+        .parse::<usize>()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, flag))
+}
+
+fn parse_path(flag: &str, value: Option<String>) -> Result<PathBuf, String> {
+    value
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("missing value for `{}`", flag))
+}
+
+fn parse_day(day: u8) -> Result<u8, String> {
+    if (1..=25).contains(&day) {
+        Ok(day)
+    } else {
+        Err(format!("day must be between 1 and 25, got {}", day))
+    }
+}
+
+fn parse_part(part: u8) -> Result<Part, String> {
+    Part::from_number(part).ok_or_else(|| format!("part must be 1 or 2, got {}", part))
+}
+
+/// Handles the input options shared by every command that solves puzzles.
+fn parse_source<I>(flag: &str, args: &mut I, current: Source) -> Result<Source, String>
+where
+    I: Iterator<Item = String>,
+{
+    let source = match flag {
+        "--example" | "-e" => Source::Example,
+        "--input" | "-i" => match args.next().as_deref() {
+            Some("-") => Source::Stdin,
+            Some(path) => Source::Path(PathBuf::from(path)),
+            None => return Err(format!("missing value for `{}`", flag)),
+        },
+        _ => return Err(format!("unknown option `{}`", flag)),
+    };
+
+    if current != Source::Input {
+        return Err("`--input` and `--example` can only be given once".to_string());
+    }
+
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        Command::parse(args.split_ascii_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parse_run() {
+        assert_eq!(
+            parse("run --day 8 --part 2"),
+            Ok(Command::Run {
+                day: 8,
+                part: Part::Two,
+                source: Source::Input,
+                view: View::default(),
+            })
+        );
+        assert_eq!(
+            parse("run -p 1 -d 14"),
+            Ok(Command::Run {
+                day: 14,
+                part: Part::One,
+                source: Source::Input,
+                view: View::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_run_sources() {
+        assert_eq!(
+            parse("run --day 8 --part 2 --example"),
+            Ok(Command::Run {
+                day: 8,
+                part: Part::Two,
+                source: Source::Example,
+                view: View::default(),
+            })
+        );
+        assert_eq!(
+            parse("run --day 8 --input - --part 2"),
+            Ok(Command::Run {
+                day: 8,
+                part: Part::Two,
+                source: Source::Stdin,
+                view: View::default(),
+            })
+        );
+        assert_eq!(
+            parse("run --day 8 --part 2 -i /tmp/day8.txt"),
+            Ok(Command::Run {
+                day: 8,
+                part: Part::Two,
+                source: Source::Path(PathBuf::from("/tmp/day8.txt")),
+                view: View::default(),
+            })
+        );
+        assert_eq!(
+            parse("run --day 14 --part 1 --render"),
+            Ok(Command::Run {
+                day: 14,
+                part: Part::One,
+                source: Source::Input,
+                view: View {
+                    render: true,
+                    ..View::default()
+                },
+            })
+        );
+        assert_eq!(
+            parse("run --day 10 --part 2 --image maze.svg --palette #000000,#ffffff --cell-size 4"),
+            Ok(Command::Run {
+                day: 10,
+                part: Part::Two,
+                source: Source::Input,
+                view: View {
+                    render: false,
+                    image: Some(PathBuf::from("maze.svg")),
+                    palette: Some(Palette::parse("#000000,#ffffff").unwrap()),
+                    cell_size: Some(4),
+                },
+            })
+        );
+        assert!(parse("run --day 10 --part 2 --image maze.svg --cell-size 0").is_err());
+        assert!(parse("run --day 10 --part 2 --image maze.svg --palette red").is_err());
+        assert!(parse("run --day 10 --part 2 --cell-size 4").is_err());
+        assert!(parse("run --day 8 --part 2 --input").is_err());
+        assert!(parse("run --day 8 --part 2 --example --input -").is_err());
+    }
+
+    #[test]
+    fn parse_all() {
+        assert_eq!(
+            parse("all"),
+            Ok(Command::All {
+                source: Source::Input
+            })
+        );
+        assert_eq!(
+            parse("all --example"),
+            Ok(Command::All {
+                source: Source::Example
+            })
+        );
+        assert!(parse("all --input -").is_err());
+        assert!(parse("all --day 8").is_err());
+        assert_eq!(parse("verify"), Ok(Command::Verify));
+    }
+
+    #[test]
+    fn parse_bench() {
+        assert_eq!(
+            parse("bench"),
+            Ok(Command::Bench(bench::Options::default()))
+        );
+        assert_eq!(
+            parse("bench --day 14 --part 1 --runs 20 --warmup 0 --example --json after.json --baseline before.json"),
+            Ok(Command::Bench(bench::Options {
+                day: Some(14),
+                part: Some(Part::One),
+                runs: 20,
+                warmup: 0,
+                source: Source::Example,
+                json: Some(PathBuf::from("after.json")),
+                baseline: Some(PathBuf::from("before.json")),
+            }))
+        );
+        assert!(parse("bench --runs 0").is_err());
+        assert!(parse("bench --input -").is_err());
+        assert!(parse("bench --day 11 --input -").is_ok());
+    }
+
+    #[test]
+    fn parse_new_day() {
+        assert_eq!(parse("new-day 12"), Ok(Command::NewDay { day: 12 }));
+        assert!(parse("new-day").is_err());
+        assert!(parse("new-day 26").is_err());
+        assert!(parse("new-day 12 --force").is_err());
+    }
+
+    #[test]
+    fn parse_fetch() {
+        assert_eq!(parse("fetch --day 12"), Ok(Command::Fetch { day: 12 }));
+        assert!(parse("fetch").is_err());
+        assert!(parse("fetch --day 12 --example").is_err());
+    }
+
+    #[test]
+    fn parse_submit() {
+        assert_eq!(
+            parse("submit --day 12 --part 2"),
+            Ok(Command::Submit {
+                day: 12,
+                part: Part::Two
+            })
+        );
+        assert!(parse("submit --day 12").is_err());
+        assert!(parse("submit --day 12 --part 1 --example").is_err());
+    }
+
+    #[test]
+    fn parse_calibrate() {
+        assert_eq!(
+            parse("calibrate"),
+            Ok(Command::Calibrate(day1::Options::default()))
+        );
+        assert_eq!(
+            parse("calibrate --lang es --example"),
+            Ok(Command::Calibrate(day1::Options {
+                language: Some("es".to_string()),
+                vocab: None,
+                part: Part::Two,
+                explain: false,
+                source: Source::Example,
+            }))
+        );
+        assert_eq!(
+            parse("calibrate --vocab words.txt --input - --explain"),
+            Ok(Command::Calibrate(day1::Options {
+                language: None,
+                vocab: Some(PathBuf::from("words.txt")),
+                part: Part::Two,
+                explain: true,
+                source: Source::Stdin,
+            }))
+        );
+        assert!(parse("calibrate --lang").is_err());
+        assert!(parse("calibrate --lang es --vocab words.txt").is_err());
+        assert_eq!(
+            parse("calibrate --part 1 --explain"),
+            Ok(Command::Calibrate(day1::Options {
+                part: Part::One,
+                explain: true,
+                ..day1::Options::default()
+            }))
+        );
+        assert!(parse("calibrate --part 1 --lang es").is_err());
+    }
+
+    #[test]
+    fn parse_help() {
+        assert_eq!(parse(""), Ok(Command::Help));
+        assert_eq!(parse("--help"), Ok(Command::Help));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("run --day 8").is_err());
+        assert!(parse("run --day 26 --part 1").is_err());
+        assert!(parse("run --day 8 --part 3").is_err());
+        assert!(parse("run --day eight --part 1").is_err());
+        assert!(parse("run --day 8 --part 1 --fast").is_err());
+        assert!(parse("jump").is_err());
+    }
+}
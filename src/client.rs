@@ -0,0 +1,212 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// HTTP client for the puzzle website, logged in through the session cookie.
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Result<Self, String> {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(&config.user_agent())
+            .timeout(Duration::from_secs(30))
+            .build();
+
+        Ok(Self {
+            agent,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            session: config.session()?.to_string(),
+        })
+    }
+
+    /// Fetches `path`, relative to the base URL, and returns the body.
+    pub fn get(&self, path: &str) -> Result<String, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session));
+
+        read_response(&url, request.call())
+    }
+
+    /// Posts `fields` as a form to `path` and returns the body.
+    pub fn post_form(&self, path: &str, fields: &[(&str, &str)]) -> Result<String, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let request = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session));
+
+        read_response(&url, request.send_form(fields))
+    }
+
+    pub fn input(&self, day: u8) -> Result<String, String> {
+        self.get(&format!("/day/{}/input", day))
+    }
+}
+
+fn read_response(
+    url: &str,
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<String, String> {
+    match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| format!("{}: {}", url, e)),
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            let reason = body.lines().next().unwrap_or_default().trim();
+            Err(format!("{}: HTTP {} {}", url, status, reason)
+                .trim_end()
+                .to_string())
+        }
+        Err(e) => Err(format!("{}: {}", url, e)),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded,
+    /// The file already held an input, nothing was requested.
+    Cached,
+}
+
+/// Downloads the input of `day` into `path` unless it is already there.
+///
+/// Empty files, like the ones `new-day` creates, do not count as cached.
+/// The input is written to a temporary file first so an interrupted
+/// download never looks like a cached one.
+pub fn fetch_input(config: &Config, day: u8, path: &Path) -> Result<Fetched, String> {
+    let is_cached = fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0);
+    if is_cached {
+        return Ok(Fetched::Cached);
+    }
+
+    let input = Client::new(config)?.input(day)?;
+
+    let write_error = |path: &Path, e: std::io::Error| format!("{}: {}", path.display(), e);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| write_error(dir, e))?;
+    }
+    let partial = path.with_extension("txt.part");
+    fs::write(&partial, input).map_err(|e| write_error(&partial, e))?;
+    fs::rename(&partial, path).map_err(|e| write_error(path, e))?;
+
+    Ok(Fetched::Downloaded)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::thread::{self, JoinHandle};
+
+    /// Answers `responses.len()` requests with the given status and body,
+    /// then returns the raw requests it received.
+    pub fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2023", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                requests.push(request);
+
+                let response = format!(
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+
+        (base_url, handle)
+    }
+
+    pub fn stub_config(base_url: String) -> Config {
+        Config {
+            session: Some("abc123".to_string()),
+            base_url,
+            contact: Some("me@example.com".to_string()),
+        }
+    }
+
+    fn temp_input(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("12").join("input.txt")
+    }
+
+    #[test]
+    fn fetch_and_cache_input() {
+        let (base_url, server) = stub_server(vec![(200, "1 2 3\n4 5 6\n")]);
+        let config = stub_config(base_url);
+        let path = temp_input("fetch");
+
+        let first = fetch_input(&config, 12, &path);
+        // the stub only answers once, a second request would fail
+        let second = fetch_input(&config, 12, &path);
+        let requests = server.join().unwrap();
+
+        assert_eq!(first, Ok(Fetched::Downloaded));
+        assert_eq!(second, Ok(Fetched::Cached));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2 3\n4 5 6\n");
+        assert_eq!(requests.len(), 1);
+        let request = requests[0].to_ascii_lowercase();
+        assert!(request.starts_with("get /2023/day/12/input http/1.1\r\n"));
+        assert!(request.contains("\r\ncookie: session=abc123\r\n"));
+        assert!(request.contains("\r\nuser-agent: adventofcode2023/"));
+        assert!(request.contains("(me@example.com)\r\n"));
+
+        fs::remove_dir_all(path.parent().unwrap().parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn fetch_input_failure() {
+        let (base_url, server) = stub_server(vec![(404, "Not Found")]);
+        let config = stub_config(base_url);
+        let path = temp_input("fetch-failure");
+
+        let result = fetch_input(&config, 12, &path);
+        server.join().unwrap();
+
+        assert!(result
+            .unwrap_err()
+            .ends_with("/2023/day/12/input: HTTP 404 Not Found"));
+        assert!(!path.exists());
+        assert!(fetch_input(&Config::default(), 12, &path).is_err());
+    }
+}
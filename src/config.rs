@@ -0,0 +1,138 @@
+use std::env;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::utils::{open_file, parse_key_values};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2023";
+
+/// Settings for talking to the puzzle website.
+///
+/// They are read from the config file, see [`Config::path`], and the
+/// `AOC_SESSION` and `AOC_BASE_URL` environment variables, which win over
+/// the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Value of the `session` cookie of a logged in browser.
+    pub session: Option<String>,
+    pub base_url: String,
+    /// Added to the User-Agent so the site owner knows who to reach.
+    pub contact: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            contact: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        for (index, key, value) in parse_key_values(input)? {
+            match key {
+                "session" => config.session = Some(value.to_string()),
+                "base_url" => config.base_url = value.to_string(),
+                "contact" => config.contact = Some(value.to_string()),
+                key => return Err(format!("line {}: unknown key `{}`", index + 1, key)),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// `AOC_CONFIG` when set, otherwise `adventofcode2023/config.toml` in the
+    /// user's config directory.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("AOC_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("adventofcode2023").join("config.toml"))
+    }
+
+    /// A missing config file is fine as long as the environment provides what
+    /// is needed.
+    pub fn load() -> Result<Self, String> {
+        let mut config = match Self::path() {
+            Some(path) => match open_file(&path) {
+                Ok(contents) => {
+                    Self::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))?
+                }
+                Err(e) if e.kind() == ErrorKind::NotFound => Self::default(),
+                Err(e) => return Err(format!("{}: {}", path.display(), e)),
+            },
+            None => Self::default(),
+        };
+
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+
+        Ok(config)
+    }
+
+    pub fn session(&self) -> Result<&str, String> {
+        self.session
+            .as_deref()
+            .map(str::trim)
+            .filter(|session| !session.is_empty())
+            .ok_or_else(|| {
+                "no session token, set AOC_SESSION or `session` in the config file".to_string()
+            })
+    }
+
+    pub fn user_agent(&self) -> String {
+        let agent = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+        match &self.contact {
+            Some(contact) => format!("{} ({})", agent, contact),
+            None => agent.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let input = "# adventofcode2023
+session = \"53616c7465645f5f\"
+base_url = \"http://127.0.0.1:8080\"
+contact = \"me@example.com\"
+";
+
+        let config = Config::parse(input).unwrap();
+
+        assert_eq!(config.session(), Ok("53616c7465645f5f"));
+        assert_eq!(config.base_url, "http://127.0.0.1:8080");
+        assert_eq!(
+            config.user_agent(),
+            format!(
+                "adventofcode2023/{} (me@example.com)",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+    }
+
+    #[test]
+    fn parse_default_config() {
+        let config = Config::parse("").unwrap();
+
+        assert_eq!(config, Config::default());
+        assert!(config.session().is_err());
+        assert!(Config::parse("token = \"abc\"").is_err());
+    }
+}
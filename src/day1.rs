@@ -1,49 +1,457 @@
-use regex::Regex;
-
-use crate::utils::open_file;
-
-pub fn day1() -> Result<i32, std::io::Error> {
-    let contents = open_file("./inputs/1/input.txt")?;
-    let result: Vec<_> = contents
-        .lines()
-        .map(map_line)
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let first = line.first().unwrap();
-            let last = line.last().unwrap_or(first);
-            format!("{}{}", first, last)
+use std::fmt::Write as _;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+use crate::error::{Error, Result};
+use crate::runner::Source;
+use crate::solution::{Part, Solution};
+use crate::utils::lines::LineReader;
+use crate::utils::matcher::{Match, Matcher};
+use crate::utils::{open_file, parse_key_values};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<String>;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part_one(lines: &Self::Input) -> Result<Self::Answer> {
+        let matcher = Vocabulary::digits().matcher();
+
+        Ok(calibration_sum(lines.iter().map(String::as_str), &matcher))
+    }
+
+    fn part_two(lines: &Self::Input) -> Result<Self::Answer> {
+        let matcher = Vocabulary::english().matcher();
+
+        Ok(calibration_sum(lines.iter().map(String::as_str), &matcher))
+    }
+}
+
+/// Options of the `calibrate` command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub language: Option<String>,
+    pub vocab: Option<PathBuf>,
+    pub part: Part,
+    pub explain: bool,
+    pub source: Source,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            language: None,
+            vocab: None,
+            part: Part::Two,
+            explain: false,
+            source: Source::Input,
+        }
+    }
+}
+
+impl Options {
+    /// No words for part one, otherwise the vocabulary file if one was
+    /// given, else the language, English by default.
+    pub fn vocabulary(&self) -> std::result::Result<Vocabulary, String> {
+        if self.part == Part::One {
+            return Ok(Vocabulary::digits());
+        }
+        match (&self.vocab, &self.language) {
+            (Some(path), _) => Vocabulary::load(path),
+            (None, Some(code)) => Vocabulary::language(code).ok_or_else(|| {
+                format!(
+                    "unknown language `{}`, expected one of {}",
+                    code,
+                    LANGUAGES.map(|(code, _)| code).join(", ")
+                )
+            }),
+            (None, None) => Ok(Vocabulary::english()),
+        }
+    }
+}
+
+/// One to nine in every built in language.
+const LANGUAGES: [(&str, [&str; 9]); 4] = [
+    (
+        "en",
+        [
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ],
+    ),
+    (
+        "es",
+        [
+            "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+        ],
+    ),
+    (
+        "fr",
+        [
+            "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+        ],
+    ),
+    (
+        "de",
+        [
+            "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ],
+    ),
+];
+
+/// Spelled out numbers and the digit each one stands for. Digits themselves
+/// always match and are not part of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+}
+
+impl Vocabulary {
+    /// No words at all, only digits are read.
+    pub fn digits() -> Self {
+        Self { words: vec![] }
+    }
+
+    /// The words of the puzzle, `zero` is not one of them.
+    pub fn english() -> Self {
+        Self::language("en").expect("English is built in")
+    }
+
+    /// One to nine in `en`, `es`, `fr` or `de`.
+    pub fn language(code: &str) -> Option<Self> {
+        let (_, words) = LANGUAGES.iter().find(|(language, _)| *language == code)?;
+        let words = (1..)
+            .zip(words)
+            .map(|(value, word)| (word.to_string(), value));
+        Some(Self {
+            words: words.collect(),
         })
-        .map(|number| number.parse::<i32>().unwrap())
-        .collect();
-
-    Ok(result.iter().sum::<i32>())
-}
-
-fn map_line(line: &str) -> Vec<&str> {
-    let re = Regex::new(r"((\d)|(one)|(two)|(three)|(four)|(five)|(six)|(seven)|(eight)|(nine))")
-        .unwrap();
-
-    let mut start = 0;
-    let mut matches = vec![];
-
-    while let Some(matched) = re.find(&line[start..]) {
-        let number_matched = &line[start + matched.start()..start + matched.end()];
-        matches.push(number_matched);
-        start += matched.start() + 1; // Aumenta la posición de inicio para buscar solapamientos
-    }
-    matches
-        .iter()
-        .map(|&m| match m {
-            "one" => "1",
-            "two" => "2",
-            "three" => "3",
-            "four" => "4",
-            "five" => "5",
-            "six" => "6",
-            "seven" => "7",
-            "eight" => "8",
-            "nine" => "9",
-            _ => m,
+    }
+
+    /// Reads `word = digit` lines, for instance `zero = 0`. `#` starts a
+    /// comment.
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut words = vec![];
+
+        for (index, word, value) in parse_key_values(text)? {
+            let value = value
+                .parse::<u32>()
+                .ok()
+                .filter(|value| *value <= 9)
+                .ok_or_else(|| format!("line {}: `{}` must be a digit", index + 1, value))?;
+            if word.is_empty() {
+                return Err(format!("line {}: missing word", index + 1));
+            }
+            words.push((word.to_string(), value));
+        }
+
+        Ok(Self { words })
+    }
+
+    pub fn load(path: &Path) -> std::result::Result<Self, String> {
+        open_file(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Self::parse(&text))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Matches digits as well as every word of the vocabulary.
+    pub fn matcher(&self) -> Matcher<u32> {
+        let digits = (0..=9).map(|digit: u32| (digit.to_string(), digit));
+        let words = self
+            .words
+            .iter()
+            .map(|(word, value)| (word.clone(), *value));
+
+        Matcher::new(digits.chain(words))
+    }
+}
+
+/// Chunks handed to the worker threads are cut at the first line boundary
+/// past this many bytes.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// Sum of the calibration values of `input` read with `vocabulary`.
+pub fn calibrate(input: &str, vocabulary: &Vocabulary) -> u64 {
+    calibration_sum(input.lines(), &vocabulary.matcher())
+}
+
+/// Like [`calibrate`] but streams the input from `source` and sums it on
+/// every core, so files larger than memory are fine.
+pub fn calibrate_source(source: &Source, vocabulary: &Vocabulary) -> Result<u64> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let matcher = vocabulary.matcher();
+
+    match source.path(Day1::DAY) {
+        Some(path) => LineReader::open(&path)
+            .and_then(|lines| calibrate_parallel(lines, &matcher, threads))
+            .map_err(|e| Error::io(path.display(), e)),
+        None => calibrate_parallel(LineReader::new(io::stdin().lock()), &matcher, threads)
+            .map_err(|e| Error::io("stdin", e)),
+    }
+}
+
+fn calibrate_parallel<R: BufRead>(
+    lines: LineReader<R>,
+    matcher: &Matcher<u32>,
+    threads: usize,
+) -> io::Result<u64> {
+    sum_chunks(lines, threads, CHUNK_SIZE, |chunk| {
+        calibration_sum(chunk.lines(), matcher)
+    })
+}
+
+/// Reads `lines` into chunks of whole lines, cut past `chunk_size` bytes,
+/// and adds up `sum` of each on one of `threads` workers. At most two
+/// chunks per worker wait in memory.
+fn sum_chunks<R, F>(
+    mut lines: LineReader<R>,
+    threads: usize,
+    chunk_size: usize,
+    sum: F,
+) -> io::Result<u64>
+where
+    R: BufRead,
+    F: Fn(&str) -> u64 + Sync,
+{
+    let (sender, receiver) = mpsc::sync_channel::<String>(threads * 2);
+    let receiver = Mutex::new(receiver);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut total = 0;
+                    loop {
+                        // bound first so the lock is released before summing,
+                        // a guard in a `while let` would live for the whole body
+                        let chunk = receiver.lock().unwrap().recv();
+                        let Ok(chunk) = chunk else { break };
+                        total += sum(&chunk);
+                    }
+                    total
+                })
+            })
+            .collect();
+
+        let mut chunk = String::with_capacity(chunk_size);
+        let read = loop {
+            match lines.next_line() {
+                Some(Ok(line)) => {
+                    chunk.push_str(line);
+                    chunk.push('\n');
+                    if chunk.len() >= chunk_size {
+                        let full = std::mem::replace(&mut chunk, String::with_capacity(chunk_size));
+                        // workers only stop once the sender is dropped
+                        sender
+                            .send(full)
+                            .expect("calibration workers stopped early");
+                    }
+                }
+                Some(Err(e)) => break Err(e),
+                None => {
+                    if !chunk.is_empty() {
+                        sender
+                            .send(chunk)
+                            .expect("calibration workers stopped early");
+                    }
+                    break Ok(());
+                }
+            }
+        };
+        drop(sender);
+
+        let total = workers
+            .into_iter()
+            .map(|worker| worker.join().expect("calibration worker panicked"))
+            .sum();
+        read.map(|()| total)
+    })
+}
+
+/// Every line of `input` with the numbers found in it, the first and last
+/// ones and the resulting value, followed by the total.
+pub fn explain(input: &str, vocabulary: &Vocabulary) -> String {
+    let matcher = vocabulary.matcher();
+    let mut output = String::new();
+    let mut total = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let mut matches: Vec<_> = matcher.find_overlapping(line).collect();
+        matches.sort_by_key(|m| m.start);
+        let found: Vec<_> = matches
+            .iter()
+            .map(|m| format!("{}@{}", &line[m.start..m.end], m.start))
+            .collect();
+
+        // writing to a String cannot fail
+        let _ = match first_and_last(&matcher, line) {
+            Some((first, last)) => {
+                let value = first.value * 10 + last.value;
+                total += value;
+                writeln!(
+                    output,
+                    "{}: {} | {} | first {} last {} | {}",
+                    index + 1,
+                    line,
+                    found.join(" "),
+                    first.value,
+                    last.value,
+                    value
+                )
+            }
+            None => writeln!(output, "{}: {} | no numbers | 0", index + 1, line),
+        };
+    }
+    let _ = writeln!(output, "total {}", total);
+
+    output
+}
+
+fn calibration_sum<'a, I>(lines: I, matcher: &Matcher<u32>) -> u64
+where
+    I: Iterator<Item = &'a str>,
+{
+    lines
+        .filter_map(|line| first_and_last(matcher, line))
+        .map(|(first, last)| u64::from(first.value * 10 + last.value))
+        .sum()
+}
+
+/// The leftmost and rightmost numbers of `line`, which may overlap like in
+/// `eightwo`.
+fn first_and_last(matcher: &Matcher<u32>, line: &str) -> Option<(Match<u32>, Match<u32>)> {
+    matcher
+        .find_overlapping(line)
+        .fold(None, |found: Option<(Match<u32>, Match<u32>)>, next| {
+            let (first, last) = found.unwrap_or((next, next));
+            Some((
+                if next.start < first.start {
+                    next
+                } else {
+                    first
+                },
+                if next.start > last.start { next } else { last },
+            ))
         })
-        .collect::<Vec<_>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Barrier;
+
+    #[test]
+    fn resolve_example() {
+        let input = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+        let lines = Day1::parse(input).unwrap();
+
+        assert_eq!(Day1::part_two(&lines).unwrap(), 281);
+    }
+
+    #[test]
+    fn resolve_digits_only() {
+        let input = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+        let lines = Day1::parse(input).unwrap();
+
+        assert_eq!(Day1::part_one(&lines).unwrap(), 142);
+        assert_eq!(Day1::part_one(&Day1::parse("eightwo").unwrap()).unwrap(), 0);
+    }
+
+    #[test]
+    fn parallel_chunks() {
+        let line = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n";
+        let input = line.repeat(CHUNK_SIZE / line.len() + 2);
+        let matcher = Vocabulary::english().matcher();
+
+        for threads in [1, 4] {
+            let lines = LineReader::new(io::Cursor::new(input.as_str()));
+            assert_eq!(
+                calibrate_parallel(lines, &matcher, threads).unwrap(),
+                calibrate(&input, &Vocabulary::english())
+            );
+        }
+
+        let invalid = LineReader::new(io::Cursor::new(b"1\n\xff\n".to_vec()));
+        assert!(calibrate_parallel(invalid, &matcher, 2).is_err());
+    }
+
+    #[test]
+    fn chunks_are_summed_concurrently() {
+        // every chunk waits for one held by the other worker, summing one
+        // chunk at a time would never get past the barrier
+        let barrier = Barrier::new(2);
+        let lines = LineReader::new(io::Cursor::new("1\n2\n3\n4\n"));
+
+        let total = sum_chunks(lines, 2, 1, |chunk| {
+            barrier.wait();
+            chunk.trim().parse::<u64>().unwrap()
+        });
+
+        assert_eq!(total.unwrap(), 10);
+    }
+
+    #[test]
+    fn explain_lines() {
+        let explained = explain("xtwone3four\nabc", &Vocabulary::english());
+
+        assert_eq!(
+            explained,
+            "1: xtwone3four | two@1 one@3 3@6 four@7 | first 2 last 4 | 24
+2: abc | no numbers | 0
+total 24
+"
+        );
+        assert!(explain("two1", &Vocabulary::digits())
+            .starts_with("1: two1 | 1@3 | first 1 last 1 | 11"));
+    }
+
+    #[test]
+    fn overlapping_first_and_last() {
+        let matcher = Vocabulary::english().matcher();
+
+        let (first, last) = first_and_last(&matcher, "xeightwo").unwrap();
+
+        assert_eq!((first.start, first.value), (1, 8));
+        assert_eq!((last.start, last.end, last.value), (5, 8, 2));
+        assert_eq!(first_and_last(&matcher, "abc"), None);
+    }
+
+    #[test]
+    fn other_languages() {
+        let spanish = Vocabulary::language("es").unwrap();
+
+        assert_eq!(calibrate("xdosochocinco\nseis", &spanish), 25 + 66);
+        assert_eq!(calibrate("zero2", &Vocabulary::english()), 22);
+        assert_eq!(Vocabulary::language("xx"), None);
+    }
+
+    #[test]
+    fn parse_vocabulary() {
+        let vocabulary = Vocabulary::parse("# with zero\nzero = 0\none = 1\n").unwrap();
+
+        assert_eq!(calibrate("zeroxone\n3zero", &vocabulary), 1 + 30);
+        assert!(Vocabulary::parse("ten = 10").is_err());
+        assert!(Vocabulary::parse("ten").is_err());
+        assert!(Vocabulary::parse(" = 1").is_err());
+    }
 }
@@ -1,21 +1,41 @@
-use crate::utils::open_file;
-use queues::{IsQueue, Queue};
-use std::collections::BTreeMap;
+use crate::error::{Error, Result};
+use crate::solution::{Part, Solution};
+use crate::utils::image::Picture;
+use crate::utils::render::{Canvas, Color, Style};
+use crate::utils::search::{bfs, Search};
+use crate::utils::{Direction, Grid, Position};
 
-pub fn day10() -> Result<usize, std::io::Error> {
-    let contents = open_file("./inputs/10/input.txt")?;
+pub struct Day10;
 
-    let result = resolve_puzzle(&contents);
+impl Solution for Day10 {
+    const DAY: u8 = 10;
 
-    Ok(result)
-}
+    type Input = PipeMaze;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        PipeMaze::parse(input)
+    }
+
+    fn part_one(maze: &Self::Input) -> Result<Self::Answer> {
+        maze.pipe_loop()
+            .farthest()
+            .filter(|&(_, distance)| distance > 0)
+            .map(|(_, distance)| distance)
+            .ok_or_else(|| Error::NoSolution("the start tile is not on a loop".to_string()))
+    }
+
+    fn part_two(maze: &Self::Input) -> Result<Self::Answer> {
+        Ok(maze.enclosed_tiles() as u64)
+    }
 
-fn resolve_puzzle(input: &str) -> usize {
-    let mut maze = PipeMaze::parse(input);
-    maze.connect_pipes();
+    fn render(maze: &Self::Input, _part: Part) -> Option<Canvas> {
+        Some(maze.render())
+    }
 
-    // println!("loop one: {:?}", maze.pipe_loop);
-    maze.pipe_loop.len() / 2
+    fn picture(maze: &Self::Input, _part: Part) -> Option<Picture> {
+        Some(maze.picture())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -30,213 +50,218 @@ enum PipeType {
     Ground,
 }
 
+impl PipeType {
+    /// The pipe connecting `a` and `b`, which must differ.
+    fn joining(a: Direction, b: Direction) -> Self {
+        use Direction::*;
+        match (a, b) {
+            (North, East) | (East, North) => Self::NorthEast,
+            (North, South) | (South, North) => Self::Vertical,
+            (North, West) | (West, North) => Self::NorthWest,
+            (East, South) | (South, East) => Self::SouthEast,
+            (East, West) | (West, East) => Self::Horizontal,
+            (South, West) | (West, South) => Self::SouthWest,
+            _ => unreachable!("a pipe joins two different directions"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
-struct PipeMaze {
-    pipes: BTreeMap<(usize, usize), Pipe>,
-    start: (usize, usize),
-    pipe_loop: Vec<(usize, usize)>,
+pub struct PipeMaze {
+    pipes: Grid<Pipe>,
+    start: Position,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 struct Pipe {
     symbol: String,
     code: PipeType,
-    is_connected: bool,
-    visited: bool,
 }
 
 impl PipeMaze {
-    fn parse(input: &str) -> Self {
-        let mut start = (0, 0);
-        let pipes: BTreeMap<(usize, usize), Pipe> = input
-            .lines()
-            .enumerate()
-            .flat_map(|(x, line)| {
-                line.chars()
-                    .enumerate()
-                    .map(|(y, char)| {
-                        if char == 'S' {
-                            start = (x, y);
-                        }
-                        ((x, y), Pipe::parse(char))
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect();
-
-        Self {
-            pipes,
-            start,
-            pipe_loop: vec![start],
+    fn parse(input: &str) -> Result<Self> {
+        let pipes = Grid::parse(input, Pipe::parse)?;
+        let start = pipes
+            .iter()
+            .find(|(_, pipe)| pipe.code == PipeType::Start)
+            .map(|(position, _)| position)
+            .ok_or_else(|| Error::NoSolution("there is no start tile `S`".to_string()))?;
+
+        let mut maze = Self { pipes, start };
+        if let Some(shape) = maze.start_shape() {
+            maze.pipes[start].code = shape;
         }
+
+        Ok(maze)
     }
 
-    fn connect_pipes(&mut self) {
-        let mut current = self.start;
-        loop {
-            let mut pipe = self.pipes.get_mut(&current).unwrap().clone();
-
-            match pipe.code {
-                PipeType::Start => {
-                    if let Some(next_pipe) =
-                        pipe.clone()
-                            .find_candidates(current)
-                            .iter()
-                            .find(|candidate| {
-                                let candidate_pipe = self.pipes.get(candidate);
-                                if let Some(candidate_pipe) = candidate_pipe {
-                                    match (
-                                        current.0.checked_sub(candidate.0),
-                                        current.1.checked_sub(candidate.1),
-                                    ) {
-                                        (Some(0), _) => {
-                                            pipe.is_connected = true;
-                                            pipe.visited = true;
-                                            candidate_pipe.code == PipeType::Horizontal
-                                                || candidate_pipe.code == PipeType::NorthWest
-                                                || candidate_pipe.code == PipeType::NorthEast
-                                        }
-                                        (_, Some(0)) => {
-                                            pipe.is_connected = true;
-                                            pipe.visited = true;
-                                            candidate_pipe.code == PipeType::Vertical
-                                                || candidate_pipe.code == PipeType::SouthWest
-                                                || candidate_pipe.code == PipeType::SouthEast
-                                        }
-                                        _ => false,
-                                    }
-                                } else {
-                                    false
-                                }
-                            })
-                    {
-                        current = *next_pipe;
-                        self.pipe_loop.push(current);
-                    }
-                }
-                PipeType::Ground => {
-                    panic!("Ground found");
+    /// The pipe hidden below `S`. Walks out of the start along every pipe
+    /// connecting back to it, the first walk that returns tells both ends, so
+    /// dead ends pointing at `S` are ignored.
+    fn start_shape(&self) -> Option<PipeType> {
+        for first in self.connected_directions(self.start) {
+            let mut position = self.start;
+            let mut heading = first;
+
+            // pipes other than the start join exactly two cells, so a walk
+            // either comes back or stops at a dead end
+            while let Some(next) = self.pipes.step(position, heading) {
+                if next == self.start {
+                    return Some(PipeType::joining(first, heading.reverse()));
                 }
-                _ => {
-                    if let Some(next_pipe) = pipe
-                        .find_candidates(current)
-                        .iter()
-                        .find(|candidate| !self.pipe_loop.contains(candidate))
-                    {
-                        current = *next_pipe;
-                        self.pipe_loop.push(current);
-                    } else {
-                        break;
-                    }
+                let connections = self.pipes[next].connections();
+                if !connections.contains(&heading.reverse()) {
+                    break;
                 }
-            }
-
-            if current == self.start {
-                break;
+                let Some(&out) = connections.iter().find(|&&d| d != heading.reverse()) else {
+                    break;
+                };
+                position = next;
+                heading = out;
             }
         }
+
+        None
     }
 
-    fn look_external(&mut self) {
-        self.connect_pipes();
-        let current = self.start;
-        let mut queue: Queue<(usize, usize)> = Queue::new();
-        let current_pipe = self.pipes.get_mut(&current).unwrap();
+    /// Cells the pipe at `position` connects to, inside the maze.
+    fn candidates(&self, position: Position) -> Vec<Position> {
+        self.pipes[position]
+            .connections()
+            .iter()
+            .filter_map(|&direction| self.pipes.step(position, direction))
+            .collect()
+    }
 
-        current_pipe.visited = true;
-        queue.add(current).unwrap();
+    /// Directions in which the pipe at `position` connects to a pipe that
+    /// connects back.
+    fn connected_directions(&self, position: Position) -> Vec<Direction> {
+        self.pipes[position]
+            .connections()
+            .iter()
+            .copied()
+            .filter(|&direction| {
+                self.pipes
+                    .step(position, direction)
+                    .is_some_and(|candidate| {
+                        self.pipes[candidate]
+                            .connections()
+                            .contains(&direction.reverse())
+                    })
+            })
+            .collect()
+    }
 
-        while queue.size() > 0 {
-            let current = queue.remove().unwrap();
-            let current_pipe = self.pipes.get_mut(&current).unwrap().clone();
+    /// Neighbours whose pipe connects back to the one at `position`.
+    fn connected(&self, position: Position) -> Vec<Position> {
+        self.connected_directions(position)
+            .into_iter()
+            .filter_map(|direction| self.pipes.step(position, direction))
+            .collect()
+    }
 
-            let candidates: Vec<_> = current_pipe.find_candidates(current);
-            for candidate in candidates.iter() {
-                let candidate_pipe = self.pipes.get_mut(candidate).unwrap();
-                if !candidate_pipe.visited && !candidate_pipe.is_connected {
-                    candidate_pipe.visited = true;
-                    queue.add(*candidate).unwrap();
+    /// Walks the loop both ways from the start, the distances cover every
+    /// pipe in it.
+    fn pipe_loop(&self) -> Search<Position> {
+        bfs(self.start, |&position| self.connected(position))
+    }
+
+    /// Tiles inside the loop, found by flooding the outside on a grid of
+    /// twice the resolution so the water can squeeze between pipes.
+    fn enclosed(&self) -> Vec<Position> {
+        let pipe_loop = self.pipe_loop().distances;
+        let centre = |(row, col): Position| (2 * row + 1, 2 * col + 1);
+
+        let mut walls = Grid::new(
+            2 * self.pipes.height() + 1,
+            2 * self.pipes.width() + 1,
+            false,
+        );
+        for &position in pipe_loop.keys() {
+            let centre = centre(position);
+            walls[centre] = true;
+            for direction in self.connected_directions(position) {
+                if let Some(between) = walls.step(centre, direction) {
+                    walls[between] = true;
                 }
             }
         }
+
+        let outside = bfs((0, 0), |&position| {
+            walls
+                .neighbours4(position)
+                .filter(|&neighbour| !walls[neighbour])
+                .collect::<Vec<_>>()
+        });
+
+        self.pipes
+            .positions()
+            .filter(|position| {
+                !pipe_loop.contains_key(position)
+                    && !outside.distances.contains_key(&centre(*position))
+            })
+            .collect()
     }
 
-    fn enclosed_tiles(&mut self) -> usize {
-        self.look_external();
-        dbg!(self.pipes.clone());
-        self.pipes.iter().filter(|(_, pipe)| !pipe.visited).count()
+    /// Draws the maze with the loop in yellow and the enclosed tiles on blue.
+    fn render(&self) -> Canvas {
+        let pipe_loop = self.pipe_loop();
+
+        Canvas::new(&self.pipes, |pipe| (pipe.symbol.clone(), Style::default()))
+            .layer(pipe_loop.distances.into_keys(), Style::fg(Color::Yellow))
+            .layer(self.enclosed(), Style::bg(Color::Blue))
+            .layer([self.start], Style::fg(Color::Green).bold())
+    }
+
+    /// Palette indices: 0 for ground, 1 for pipes off the loop, 2 for the
+    /// loop, 3 for enclosed tiles and 4 for the start.
+    fn picture(&self) -> Picture {
+        Picture::new(&self.pipes, |pipe| {
+            usize::from(pipe.code != PipeType::Ground)
+        })
+        .layer(self.pipe_loop().distances.into_keys(), 2)
+        .layer(self.enclosed(), 3)
+        .layer([self.start], 4)
+    }
+
+    fn enclosed_tiles(&self) -> usize {
+        self.enclosed().len()
     }
 }
 
 impl Pipe {
-    fn parse(input: char) -> Self {
+    fn parse(input: char) -> Option<Self> {
         let (symbol, code) = match input {
             '-' => ("↔".to_string(), PipeType::Horizontal),
-            '|' => ("↕️".to_string(), PipeType::Vertical),
+            '|' => ("↕".to_string(), PipeType::Vertical),
             'L' => ("↳".to_string(), PipeType::NorthEast),
             'J' => ("↲".to_string(), PipeType::NorthWest),
             '7' => ("↰".to_string(), PipeType::SouthWest),
             'F' => ("↱".to_string(), PipeType::SouthEast),
-            'S' => ("🏁".to_string(), PipeType::Start),
-            _ => ("🚫".to_string(), PipeType::Ground),
+            'S' => ("S".to_string(), PipeType::Start),
+            '.' => (".".to_string(), PipeType::Ground),
+            _ => return None,
         };
-        Self {
-            code,
-            symbol,
-            is_connected: false,
-            visited: true,
-        }
+        Some(Self { code, symbol })
     }
 
-    fn find_candidates(self, current: (usize, usize)) -> Vec<(usize, usize)> {
+    /// Directions of the cells this pipe connects.
+    fn connections(&self) -> &'static [Direction] {
+        use Direction::*;
         match self.code {
-            PipeType::Start => [(-1, 0), (0, 1), (1, 0), (0, -1)]
-                .iter()
-                .map(|(x, y)| (current.0 as i64 + x, current.1 as i64 + y))
-                .filter(|(x, y)| *x >= 0 && *y >= 0)
-                .map(|(x, y)| (x as usize, y as usize))
-                .collect(),
-            PipeType::Horizontal => [(0, -1), (0, 1)]
-                .iter()
-                .map(|(x, y)| (current.0 as i64 + x, current.1 as i64 + y))
-                .filter(|(x, y)| *x >= 0 && *y >= 0)
-                .map(|(x, y)| (x as usize, y as usize))
-                .collect(),
-            PipeType::Vertical => [(-1, 0), (1, 0)]
-                .iter()
-                .map(|(x, y)| (current.0 as i64 + x, current.1 as i64 + y))
-                .filter(|(x, y)| *x >= 0 && *y >= 0)
-                .map(|(x, y)| (x as usize, y as usize))
-                .collect(),
-            PipeType::NorthEast => [(-1, 0), (0, 1)]
-                .iter()
-                .map(|(x, y)| (current.0 as i64 + x, current.1 as i64 + y))
-                .filter(|(x, y)| *x >= 0 && *y >= 0)
-                .map(|(x, y)| (x as usize, y as usize))
-                .collect(),
-            PipeType::NorthWest => [(-1, 0), (0, -1)]
-                .iter()
-                .map(|(x, y)| (current.0 as i64 + x, current.1 as i64 + y))
-                .filter(|(x, y)| *x >= 0 && *y >= 0)
-                .map(|(x, y)| (x as usize, y as usize))
-                .collect(),
-            PipeType::SouthEast => [(1, 0), (0, 1)]
-                .iter()
-                .map(|(x, y)| (current.0 as i64 + x, current.1 as i64 + y))
-                .filter(|(x, y)| *x >= 0 && *y >= 0)
-                .map(|(x, y)| (x as usize, y as usize))
-                .collect(),
-            PipeType::SouthWest => [(1, 0), (0, -1)]
-                .iter()
-                .map(|(x, y)| (current.0 as i64 + x, current.1 as i64 + y))
-                .filter(|(x, y)| *x >= 0 && *y >= 0)
-                .map(|(x, y)| (x as usize, y as usize))
-                .collect(),
-            _ => unreachable!(),
+            PipeType::Start => &Direction::ALL,
+            PipeType::Horizontal => &[West, East],
+            PipeType::Vertical => &[North, South],
+            PipeType::NorthEast => &[North, East],
+            PipeType::NorthWest => &[North, West],
+            PipeType::SouthEast => &[South, East],
+            PipeType::SouthWest => &[South, West],
+            PipeType::Ground => &[],
         }
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -248,11 +273,12 @@ mod tests {
 .L-J.
 .....";
 
-        let result = resolve_puzzle(input);
+        let result = Day10::part_one(&Day10::parse(input).unwrap()).unwrap();
 
         assert_eq!(result, 4);
     }
 
+    #[test]
     fn test_resolve_puzzle_2() {
         let input = "..F7.
 .FJ|.
@@ -260,7 +286,7 @@ SJ.L7
 |F--J
 LJ...";
 
-        let result = resolve_puzzle(input);
+        let result = Day10::part_one(&Day10::parse(input).unwrap()).unwrap();
 
         assert_eq!(result, 8);
     }
@@ -273,46 +299,56 @@ LJ...";
 .L-J.
 .....";
 
-        let result = PipeMaze::parse(input);
-
-        let values: Vec<_> = vec![
-            ((0, 0), Pipe::parse('.')),
-            ((0, 1), Pipe::parse('.')),
-            ((0, 2), Pipe::parse('.')),
-            ((0, 3), Pipe::parse('.')),
-            ((0, 4), Pipe::parse('.')),
-            ((1, 0), Pipe::parse('.')),
-            ((1, 1), Pipe::parse('S')),
-            ((1, 2), Pipe::parse('-')),
-            ((1, 3), Pipe::parse('7')),
-            ((1, 4), Pipe::parse('.')),
-            ((2, 0), Pipe::parse('.')),
-            ((2, 1), Pipe::parse('|')),
-            ((2, 2), Pipe::parse('.')),
-            ((2, 3), Pipe::parse('|')),
-            ((2, 4), Pipe::parse('.')),
-            ((3, 0), Pipe::parse('.')),
-            ((3, 1), Pipe::parse('L')),
-            ((3, 2), Pipe::parse('-')),
-            ((3, 3), Pipe::parse('J')),
-            ((3, 4), Pipe::parse('.')),
-            ((4, 0), Pipe::parse('.')),
-            ((4, 1), Pipe::parse('.')),
-            ((4, 2), Pipe::parse('.')),
-            ((4, 3), Pipe::parse('.')),
-            ((4, 4), Pipe::parse('.')),
+        let result = PipeMaze::parse(input).unwrap();
+
+        let expected: Vec<_> = vec![
+            ((0, 0), Pipe::parse('.').unwrap()),
+            ((0, 1), Pipe::parse('.').unwrap()),
+            ((0, 2), Pipe::parse('.').unwrap()),
+            ((0, 3), Pipe::parse('.').unwrap()),
+            ((0, 4), Pipe::parse('.').unwrap()),
+            ((1, 0), Pipe::parse('.').unwrap()),
+            (
+                (1, 1),
+                Pipe {
+                    symbol: "S".to_string(),
+                    code: PipeType::SouthEast,
+                },
+            ),
+            ((1, 2), Pipe::parse('-').unwrap()),
+            ((1, 3), Pipe::parse('7').unwrap()),
+            ((1, 4), Pipe::parse('.').unwrap()),
+            ((2, 0), Pipe::parse('.').unwrap()),
+            ((2, 1), Pipe::parse('|').unwrap()),
+            ((2, 2), Pipe::parse('.').unwrap()),
+            ((2, 3), Pipe::parse('|').unwrap()),
+            ((2, 4), Pipe::parse('.').unwrap()),
+            ((3, 0), Pipe::parse('.').unwrap()),
+            ((3, 1), Pipe::parse('L').unwrap()),
+            ((3, 2), Pipe::parse('-').unwrap()),
+            ((3, 3), Pipe::parse('J').unwrap()),
+            ((3, 4), Pipe::parse('.').unwrap()),
+            ((4, 0), Pipe::parse('.').unwrap()),
+            ((4, 1), Pipe::parse('.').unwrap()),
+            ((4, 2), Pipe::parse('.').unwrap()),
+            ((4, 3), Pipe::parse('.').unwrap()),
+            ((4, 4), Pipe::parse('.').unwrap()),
         ];
 
-        let expected = values.into_iter().collect::<BTreeMap<_, _>>();
-
-        assert_eq!(result.pipes, expected);
+        assert_eq!((result.pipes.height(), result.pipes.width()), (5, 5));
+        for (position, pipe) in expected {
+            assert_eq!(result.pipes[position], pipe);
+        }
         assert_eq!(result.start, (1, 1));
     }
 
+    fn candidates(tiles: &str, position: Position) -> Vec<Position> {
+        PipeMaze::parse(tiles).unwrap().candidates(position)
+    }
+
     #[test]
     fn start_candidates() {
-        let start_pipe = Pipe::parse('S');
-        let result = start_pipe.find_candidates((1, 1));
+        let result = candidates("...\n.S.\n...", (1, 1));
 
         let expected = vec![(0, 1), (1, 2), (2, 1), (1, 0)];
 
@@ -321,8 +357,7 @@ LJ...";
 
     #[test]
     fn start_candidates_at_corner() {
-        let start_pipe = Pipe::parse('S');
-        let result = start_pipe.find_candidates((0, 0));
+        let result = candidates("S..\n...\n...", (0, 0));
 
         let expected = vec![(0, 1), (1, 0)];
 
@@ -331,8 +366,7 @@ LJ...";
 
     #[test]
     fn horizontal_candidates() {
-        let start_pipe = Pipe::parse('-');
-        let result = start_pipe.find_candidates((1, 1));
+        let result = candidates("...\n.-.\n..S", (1, 1));
 
         let expected = vec![(1, 0), (1, 2)];
 
@@ -341,8 +375,7 @@ LJ...";
 
     #[test]
     fn vertical_candidates() {
-        let start_pipe = Pipe::parse('|');
-        let result = start_pipe.find_candidates((1, 1));
+        let result = candidates("...\n.|.\n..S", (1, 1));
 
         let expected = vec![(0, 1), (2, 1)];
 
@@ -351,8 +384,7 @@ LJ...";
 
     #[test]
     fn north_east_candidates() {
-        let start_pipe = Pipe::parse('L');
-        let result = start_pipe.find_candidates((1, 1));
+        let result = candidates("...\n.L.\n..S", (1, 1));
 
         let expected = vec![(0, 1), (1, 2)];
 
@@ -361,15 +393,39 @@ LJ...";
 
     #[test]
     fn north_west_candidates() {
-        let start_pipe = Pipe::parse('J');
-        let result = start_pipe.find_candidates((1, 1));
+        let result = candidates("...\n.J.\n..S", (1, 1));
 
         let expected = vec![(0, 1), (1, 0)];
 
         assert_eq!(result, expected);
     }
 
-    // #[test]
+    #[test]
+    fn render_maze() {
+        let maze = PipeMaze::parse("S-7.\n|.|.\nL-J.").unwrap();
+
+        assert_eq!(maze.render().render(false), "S↔↰.\n↕.↕.\n↳↔↲.");
+        assert_eq!(maze.enclosed(), vec![(1, 1)]);
+        // the enclosed tile gets palette index 3, grey-blue by default
+        let ppm = maze.picture().cell_size(1).to_ppm();
+        assert_eq!(ppm[11 + 5 * 3..11 + 6 * 3], [0x3a, 0x7b, 0xd5]);
+    }
+
+    #[test]
+    fn start_shape_ignores_dead_ends() {
+        let input = ".|...
+-S-7.
+.|.|.
+.L-J.";
+
+        let maze = PipeMaze::parse(input).unwrap();
+
+        assert_eq!(maze.pipes[maze.start].code, PipeType::SouthEast);
+        assert_eq!(Day10::part_one(&maze).unwrap(), 4);
+        assert_eq!(Day10::part_two(&maze).unwrap(), 1);
+    }
+
+    #[test]
     fn enclosed_tiles_1() {
         let input = "...........
 .S-------7.
@@ -381,12 +437,12 @@ LJ...";
 .L--J.L--J.
 ...........";
 
-        let mut result = PipeMaze::parse(input);
+        let result = PipeMaze::parse(input).unwrap();
 
         assert_eq!(result.enclosed_tiles(), 4);
     }
 
-    // #[test]
+    #[test]
     fn enclosed_tiles_2() {
         let input = ".F----7F7F7F7F-7....
 .|F--7||||||||FJ....
@@ -398,12 +454,12 @@ L--J.L7...LJS7F-7L7.
 .....|FJLJ|FJ|F7|.LJ
 ....FJL-7.||.||||...
 ....L---J.LJ.LJLJ...";
-        let mut result = PipeMaze::parse(input);
+        let result = PipeMaze::parse(input).unwrap();
 
         assert_eq!(result.enclosed_tiles(), 8);
     }
 
-    // #[test]
+    #[test]
     fn enclosed_tiles_3() {
         let input = "FF7FSF7F7F7F7F7F---7
 L|LJ||||||||||||F--J
@@ -416,7 +472,7 @@ L---JF-JLJ.||-FJLJJ7
 L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L";
 
-        let mut result = PipeMaze::parse(input);
+        let result = PipeMaze::parse(input).unwrap();
 
         assert_eq!(result.enclosed_tiles(), 10);
     }
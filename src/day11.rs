@@ -1,40 +1,65 @@
 use std::collections::BTreeMap;
 
-use crate::utils::open_file;
+use crate::error::Result;
+use crate::solution::{Part, Solution};
+use crate::utils::image::Picture;
+use crate::utils::render::{Canvas, Color, Style};
+use crate::utils::{Grid, Position};
 
-pub fn day11() -> Result<usize, std::io::Error> {
-    let contents = open_file("./inputs/11/input.txt")?;
-    let result = resolve_puzzle(&contents);
+pub struct Day11;
 
-    Ok(result)
-}
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Universe;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Universe::parse(input)
+    }
+
+    fn part_one(universe: &Self::Input) -> Result<Self::Answer> {
+        let mut universe = universe.clone();
+        universe.expand();
 
-fn resolve_puzzle(input: &str) -> usize {
-    let mut universe = Universe::parse(input);
-    universe.expand();
+        Ok(universe.get_all_distances())
+    }
 
-    universe.get_all_distances()
+    fn render(universe: &Self::Input, _part: Part) -> Option<Canvas> {
+        let mut universe = universe.clone();
+        universe.expand();
+
+        Some(universe.render())
+    }
+
+    fn picture(universe: &Self::Input, _part: Part) -> Option<Picture> {
+        let mut universe = universe.clone();
+        universe.expand();
+
+        Some(universe.picture())
+    }
 }
 
-struct Universe {
-    grid: Vec<(usize, usize)>,
+#[derive(Clone)]
+pub struct Universe {
+    /// Positions of the galaxies.
+    grid: Vec<Position>,
 }
 
 impl Universe {
-    fn parse(input: &str) -> Self {
-        let grid = input
-            .lines()
-            .enumerate()
-            .flat_map(|(x, line)| {
-                line.chars().enumerate().filter_map(move |(y, c)| match c {
-                    '#' => Some((x, y)),
-                    '.' => None,
-                    _ => panic!("invalid char"),
-                })
-            })
+    fn parse(input: &str) -> Result<Self> {
+        let image = Grid::parse(input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        let grid = image
+            .iter()
+            .filter(|&(_, &galaxy)| galaxy)
+            .map(|(position, _)| position)
             .collect();
 
-        Self { grid }
+        Ok(Self { grid })
     }
 
     fn expand(&mut self) {
@@ -54,6 +79,55 @@ impl Universe {
         self.grid = grid;
     }
 
+    /// The galaxies as a grid just big enough to hold them.
+    fn image(&self) -> Grid<bool> {
+        let height = self.grid.iter().map(|&(row, _)| row + 1).max().unwrap_or(0);
+        let width = self.grid.iter().map(|&(_, col)| col + 1).max().unwrap_or(0);
+        let mut image = Grid::new(height, width, false);
+        for &galaxy in &self.grid {
+            image[galaxy] = true;
+        }
+        image
+    }
+
+    /// Cells on a row or column without galaxies, the ones that expand.
+    fn empty_space(image: &Grid<bool>) -> Vec<Position> {
+        let empty_rows: Vec<_> = image.rows().map(|row| !row.contains(&true)).collect();
+        let empty_cols: Vec<_> = image
+            .columns()
+            .map(|mut col| !col.any(|&galaxy| galaxy))
+            .collect();
+
+        image
+            .positions()
+            .filter(|&(row, col)| empty_rows[row] || empty_cols[col])
+            .collect()
+    }
+
+    /// Draws the galaxies, the empty rows and columns that expand are shaded.
+    fn render(&self) -> Canvas {
+        let image = self.image();
+
+        Canvas::new(&image, |&galaxy| {
+            if galaxy {
+                ("#".to_string(), Style::fg(Color::Yellow).bold())
+            } else {
+                (".".to_string(), Style::default())
+            }
+        })
+        .layer(Self::empty_space(&image), Style::bg(Color::Blue))
+    }
+
+    /// Palette indices: 0 for space, 1 for empty rows and columns, 2 for
+    /// galaxies.
+    fn picture(&self) -> Picture {
+        let image = self.image();
+
+        Picture::new(&image, |&galaxy| if galaxy { 2 } else { 0 })
+            .layer(Self::empty_space(&image), 1)
+            .layer(self.grid.iter().copied(), 2)
+    }
+
     fn get_all_distances(&self) -> usize {
         let grid = self.grid.clone();
 
@@ -75,7 +149,9 @@ impl Universe {
 }
 
 fn expand_galaxy_indices(galaxy_rows: &[usize]) -> BTreeMap<usize, usize> {
-    let first = *galaxy_rows.first().unwrap();
+    let Some(&first) = galaxy_rows.first() else {
+        return BTreeMap::new();
+    };
     let mut expanded_indices = BTreeMap::from([(first, first)]);
     let mut additional_gap = 0;
     let values = galaxy_rows;
@@ -84,24 +160,30 @@ fn expand_galaxy_indices(galaxy_rows: &[usize]) -> BTreeMap<usize, usize> {
         .iter()
         .zip(values.iter().skip(1))
         .for_each(|(&c, &n)| {
-            match n - c {
-                2 => {
-                    additional_gap += 1;
-                }
-                3.. => (c..n).for_each(|_| {
-                    additional_gap += 1;
-                }),
-                _ => (),
-            }
+            // every empty row between the two doubles
+            additional_gap += n - c - 1;
             expanded_indices.insert(n, n + additional_gap);
         });
 
     expanded_indices
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn picture_after_expand() {
+        let mut universe = Universe::parse("#..\n...\n..#").unwrap();
+        universe.expand();
+
+        let ppm = universe.picture().cell_size(1).to_ppm();
+
+        assert_eq!(universe.grid, vec![(0, 0), (3, 3)]);
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+    }
+
     #[test]
     fn parse_galaxy() {
         let input = "...#...
@@ -109,7 +191,7 @@ mod tests {
 .......
 #......";
 
-        let universe = Universe::parse(input);
+        let universe = Universe::parse(input).unwrap();
 
         let expected = vec![(0, 3), (1, 4), (3, 0)];
 
@@ -129,7 +211,7 @@ mod tests {
 .......#..
 #...#.....";
 
-        let mut universe = Universe::parse(input);
+        let mut universe = Universe::parse(input).unwrap();
         universe.expand();
 
         let expected = vec![
@@ -153,7 +235,7 @@ mod tests {
 
         let expanded_indices = expand_galaxy_indices(&galaxy_rows);
 
-        let expected = BTreeMap::from([(0, 0), (2, 3), (5, 9), (6, 10), (9, 16)]);
+        let expected = BTreeMap::from([(0, 0), (2, 3), (5, 8), (6, 9), (9, 14)]);
 
         assert_eq!(expanded_indices, expected);
     }
@@ -171,7 +253,7 @@ mod tests {
 .......#..
 #...#.....";
 
-        let mut universe = Universe::parse(input);
+        let mut universe = Universe::parse(input).unwrap();
         universe.expand();
 
         let distances = universe.get_all_distances();
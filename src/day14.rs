@@ -1,152 +1,153 @@
-use crate::utils::open_file;
-use std::collections::BTreeMap;
+use crate::error::Result;
+use crate::solution::{Part, Solution};
+use crate::utils::cycle::fast_forward;
+use crate::utils::image::Picture;
+use crate::utils::render::{Canvas, Color, Style};
+use crate::utils::{Direction, Grid, Position};
+use std::iter::successors;
 
-pub fn day14() -> Result<usize, std::io::Error> {
-    let contents = open_file("./inputs/14/input.txt")?;
-    let result = resolve_puzzle(&contents);
+pub struct Day14;
 
-    Ok(result)
-}
+impl Solution for Day14 {
+    const DAY: u8 = 14;
 
-fn resolve_puzzle(input: &str) -> usize {
-    let mut platform = Platform::parse(input);
-    platform.tilt_platform(TiltDirection::North);
-    platform.total_load()
-}
+    type Input = Platform;
+    type Answer = usize;
 
-fn resolve_puzzle2(input: &str) -> usize {
-    let mut platform = Platform::parse(input);
-    for _ in 0..1_000_000_000 {
-        platform.cycle();
+    fn parse(input: &str) -> Result<Self::Input> {
+        Platform::parse(input)
+    }
+
+    fn part_one(platform: &Self::Input) -> Result<Self::Answer> {
+        Ok(platform.after(Part::One).total_load())
+    }
+
+    fn part_two(platform: &Self::Input) -> Result<Self::Answer> {
+        Ok(platform.after(Part::Two).total_load())
+    }
+
+    fn render(platform: &Self::Input, part: Part) -> Option<Canvas> {
+        Some(platform.after(part).render())
     }
 
-    platform.total_load()
+    fn picture(platform: &Self::Input, part: Part) -> Option<Picture> {
+        Some(platform.after(part).picture())
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 enum TileType {
     RoundRock,
     CubeRock,
     Empty,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct Platform {
-    grid: BTreeMap<(usize, usize), TileType>,
-    rows: usize,
-    cols: usize,
-}
-
-enum TiltDirection {
-    North,
-    South,
-    East,
-    West,
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Platform {
+    grid: Grid<TileType>,
 }
 
 impl Platform {
-    fn parse(input: &str) -> Self {
-        let grid = input
-            .lines()
-            .enumerate()
-            .flat_map(|(x, line)| {
-                line.chars().enumerate().map(move |(y, c)| match c {
-                    'O' => ((y, x), TileType::RoundRock),
-                    '#' => ((y, x), TileType::CubeRock),
-                    '.' => ((y, x), TileType::Empty),
-                    _ => panic!("invalid char"),
+    fn parse(input: &str) -> Result<Self> {
+        let grid = Grid::parse(input, |c| match c {
+            'O' => Some(TileType::RoundRock),
+            '#' => Some(TileType::CubeRock),
+            '.' => Some(TileType::Empty),
+            _ => None,
+        })?;
+
+        Ok(Self { grid })
+    }
+
+    /// Every row or column of the platform, each starting at the edge the
+    /// rocks roll towards.
+    fn lanes(&self, direction: Direction) -> Vec<Vec<Position>> {
+        self.grid
+            .positions()
+            .filter(|&position| self.grid.step(position, direction).is_none())
+            .map(|edge| {
+                successors(Some(edge), |&position| {
+                    self.grid.step(position, direction.reverse())
                 })
+                .collect()
             })
-            .collect();
-        let rows = input.lines().count();
-        let cols = input.lines().next().unwrap().chars().count();
-
-        Self { grid, rows, cols }
+            .collect()
     }
 
-    fn tilt_platform(&mut self, direction: TiltDirection) {
-        use TileType::*;
-        let mut grid = self.grid.clone();
-
-        loop {
-            let mut changed = false;
-
-            for y in 0..self.cols {
-                for x in 0..self.rows {
-                    match direction {
-                        TiltDirection::North => {
-                            let current = grid.get(&(y, x));
-                            let next = grid.get(&(y, x + 1));
-
-                            if let (Some(Empty), Some(RoundRock)) = (current, next) {
-                                grid.insert((y, x), RoundRock);
-                                grid.insert((y, x + 1), Empty);
-                                changed = true;
-                            }
-                        }
-                        TiltDirection::South => {
-                            let current = grid.get(&(y, x));
-                            let next = grid.get(&(y, x.saturating_sub(1)));
-
-                            if let (Some(Empty), Some(RoundRock)) = (current, next) {
-                                grid.insert((y, x), RoundRock);
-                                grid.insert((y, x - 1), Empty);
-                                changed = true;
-                            }
-                        }
-                        TiltDirection::East => {
-                            let current = grid.get(&(y, x));
-                            let next = grid.get(&(y.saturating_sub(1), x));
-
-                            if let (Some(Empty), Some(RoundRock)) = (current, next) {
-                                grid.insert((y, x), RoundRock);
-                                grid.insert((y - 1, x), Empty);
-                                changed = true;
-                            }
-                        }
-                        TiltDirection::West => {
-                            let current = grid.get(&(y, x));
-                            let next = grid.get(&(y + 1, x));
-
-                            if let (Some(Empty), Some(RoundRock)) = (current, next) {
-                                grid.insert((y, x), RoundRock);
-                                grid.insert((y + 1, x), Empty);
-                                changed = true;
-                            }
-                        }
+    fn tilt_platform(&mut self, direction: Direction) {
+        for lane in self.lanes(direction) {
+            // the next cell a round rock can roll into
+            let mut free = 0;
+            for (index, &position) in lane.iter().enumerate() {
+                match self.grid[position] {
+                    TileType::CubeRock => free = index + 1,
+                    TileType::RoundRock => {
+                        self.grid.swap(lane[free], position);
+                        free += 1;
                     }
+                    TileType::Empty => (),
                 }
             }
+        }
+    }
 
-            if changed {
-                self.grid = grid.clone();
-            } else {
-                break;
+    fn cycle(&mut self) {
+        self.tilt_platform(Direction::North);
+        self.tilt_platform(Direction::West);
+        self.tilt_platform(Direction::South);
+        self.tilt_platform(Direction::East);
+    }
+
+    /// Where the rocks rest once `part` is solved: tilted north for part one,
+    /// after a billion spin cycles for part two.
+    fn after(&self, part: Part) -> Self {
+        match part {
+            Part::One => {
+                let mut platform = self.clone();
+                platform.tilt_platform(Direction::North);
+                platform
             }
+            Part::Two => fast_forward(
+                self.clone(),
+                |platform| {
+                    let mut platform = platform.clone();
+                    platform.cycle();
+                    platform
+                },
+                1_000_000_000,
+            ),
         }
     }
 
-    fn cycle(&mut self) {
-        self.tilt_platform(TiltDirection::North);
-        self.tilt_platform(TiltDirection::West);
-        self.tilt_platform(TiltDirection::South);
-        self.tilt_platform(TiltDirection::East);
+    /// Round rocks in bold yellow, cube rocks in blue.
+    fn render(&self) -> Canvas {
+        Canvas::new(&self.grid, |tile| match tile {
+            TileType::RoundRock => ("O".to_string(), Style::fg(Color::Yellow).bold()),
+            TileType::CubeRock => ("#".to_string(), Style::fg(Color::Blue)),
+            TileType::Empty => (".".to_string(), Style::default()),
+        })
+    }
+
+    /// Palette indices: 0 for empty cells, 1 for cube rocks, 2 for round
+    /// rocks.
+    fn picture(&self) -> Picture {
+        Picture::new(&self.grid, |tile| match tile {
+            TileType::Empty => 0,
+            TileType::CubeRock => 1,
+            TileType::RoundRock => 2,
+        })
     }
 
     fn total_load(&self) -> usize {
-        let total = self.grid.iter().fold(0, |acc, (pos, tile)| {
-            let (x, y) = pos;
-            let load = match tile {
-                TileType::RoundRock => self.rows - y,
-                _ => 0,
-            };
-
-            acc + load
-        });
-        total
+        self.grid
+            .iter()
+            .filter(|&(_, &tile)| tile == TileType::RoundRock)
+            .map(|((row, _), _)| self.grid.height() - row)
+            .sum()
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -156,7 +157,7 @@ mod tests {
 OO..#....#
 OO..O##..O";
 
-        let platform = Platform::parse(input);
+        let platform = Platform::parse(input).unwrap();
 
         let expected_values = vec![
             ((0, 0), TileType::RoundRock),
@@ -191,11 +192,11 @@ OO..O##..O";
             ((9, 2), TileType::RoundRock),
         ];
 
-        let expected = expected_values.into_iter().collect::<BTreeMap<_, _>>();
-
-        assert_eq!(platform.grid, expected);
-        assert_eq!(platform.rows, 3);
-        assert_eq!(platform.cols, 10);
+        for ((col, row), tile) in expected_values {
+            assert_eq!(platform.grid[(row, col)], tile);
+        }
+        assert_eq!(platform.grid.height(), 3);
+        assert_eq!(platform.grid.width(), 10);
     }
 
     #[test]
@@ -204,18 +205,28 @@ OO..O##..O";
 O..#....#
 OO.O##O.O";
 
-        let mut platform = Platform::parse(input);
-        platform.tilt_platform(TiltDirection::North);
+        let mut platform = Platform::parse(input).unwrap();
+        platform.tilt_platform(Direction::North);
 
         let expected_input = "OO.#.OO..
 OO.#....#
 O..O##..O";
 
-        let expected = Platform::parse(expected_input);
+        let expected = Platform::parse(expected_input).unwrap();
 
         assert_eq!(platform, expected);
     }
 
+    #[test]
+    fn render_platform() {
+        let input = "OO.#.O...
+O..#....#";
+
+        let platform = Platform::parse(input).unwrap();
+
+        assert_eq!(platform.render().render(false), input);
+    }
+
     #[test]
     fn total_load() {
         let input = "O....#....
@@ -229,8 +240,8 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let mut platform = Platform::parse(input);
-        platform.tilt_platform(TiltDirection::North);
+        let mut platform = Platform::parse(input).unwrap();
+        platform.tilt_platform(Direction::North);
         let total_load = platform.total_load();
 
         assert_eq!(total_load, 136);
@@ -248,7 +259,7 @@ O.#..O.#.#
 .......O..
 #....###..
 #OO..#....";
-        let mut platform = Platform::parse(input);
+        let mut platform = Platform::parse(input).unwrap();
         platform.cycle();
         let expected = ".....#....
 ....#...O#
@@ -261,7 +272,7 @@ O.#..O.#.#
 #...O###..
 #..OO#....";
 
-        let expected = Platform::parse(expected);
+        let expected = Platform::parse(expected).unwrap();
 
         assert_eq!(platform.grid, expected.grid);
 
@@ -277,7 +288,7 @@ O.#..O.#.#
 .......OOO
 #..OO###..
 #.OOO#...O";
-        let expected = Platform::parse(expected);
+        let expected = Platform::parse(expected).unwrap();
 
         assert_eq!(platform.grid, expected.grid);
 
@@ -293,7 +304,7 @@ O.#..O.#.#
 .......OOO
 #...O###.O
 #.OOO#...O";
-        let expected = Platform::parse(expected);
+        let expected = Platform::parse(expected).unwrap();
 
         assert_eq!(platform.grid, expected.grid);
     }
@@ -311,12 +322,8 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
-        let mut platform = Platform::parse(input);
-
-        for _ in 0..3 {
-            platform.cycle();
-        }
+        let result = Day14::part_two(&Day14::parse(input).unwrap()).unwrap();
 
-        assert_eq!(platform.total_load(), 64);
+        assert_eq!(result, 64);
     }
 }
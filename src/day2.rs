@@ -1,48 +1,211 @@
-use crate::utils::open_file;
-
-pub fn day2() -> Result<u32, std::io::Error> {
-    let contents = open_file("./inputs/2/input.txt")?;
-
-    // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-    // Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-    let result: Vec<_> = contents
-        .lines()
-        .map(|line| {
-            let mut line_iter = line.split(':');
-            let _game = line_iter
-                .next()
-                .unwrap()
-                .split_ascii_whitespace()
-                .last()
-                .unwrap();
-            let balls = line_iter
-                .next()
-                .unwrap()
-                .split(';')
-                .fold([0, 0, 0], |mut acc, set| {
-                    set.split(',').for_each(|ball| {
-                        let mut ball_iter = ball.trim().split_ascii_whitespace();
-                        let number = ball_iter.next().unwrap().parse::<u32>().unwrap();
-                        let color = ball_iter.next().unwrap();
-                        match color {
-                            "red" if acc[0] < number => acc[0] = number,
-                            "green" if acc[1] < number => acc[1] = number,
-                            "blue" if acc[2] < number => acc[2] = number,
-                            _ => (),
-                        }
-                    });
-                    acc
-                });
-            balls
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::utils::{parse_header, parse_lines, parse_number};
+
+pub struct Day2;
+
+/// Cubes of each colour drawn at once, in the order they were listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Draw {
+    pub cubes: Vec<(String, u32)>,
+}
+
+impl Draw {
+    /// How many `color` cubes were drawn, 0 if the colour is not listed.
+    pub fn count(&self, color: &str) -> u32 {
+        self.cubes
+            .iter()
+            .filter(|(name, _)| name == color)
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// Number of cubes of each colour in the bag, colours not listed have none.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bag {
+    cubes: HashMap<String, u32>,
+}
+
+impl Bag {
+    pub fn new<'a, I>(cubes: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, u32)>,
+    {
+        Self {
+            cubes: cubes
+                .into_iter()
+                .map(|(color, count)| (color.to_string(), count))
+                .collect(),
+        }
+    }
+
+    pub fn count(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    fn parse(line: &str) -> Result<Self> {
+        let (id, sets) = parse_header(line, "Game")?;
+        let draws = sets
+            .split(';')
+            .map(|set| {
+                let cubes = set
+                    .split(',')
+                    .map(|ball| {
+                        let mut ball_iter = ball.split_ascii_whitespace();
+                        let number = ball_iter.next().ok_or_else(|| {
+                            Error::parse(line, ball, "expected a number of cubes")
+                        })?;
+                        let number = parse_number::<u32>(line, number)?;
+                        let color = ball_iter
+                            .next()
+                            .ok_or_else(|| Error::parse(line, ball, "expected a colour"))?;
+                        Ok((color.to_string(), number))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Draw { cubes })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { id, draws })
+    }
+
+    /// Whether every draw could have been taken out of `bag`.
+    pub fn possible_with(&self, bag: &Bag) -> bool {
+        self.draws.iter().all(|draw| {
+            draw.cubes
+                .iter()
+                .all(|(color, _)| draw.count(color) <= bag.count(color))
         })
-        .inspect(|balls| println!("{:?}", balls))
-        .map(|balls| balls.iter().product())
-        .inspect(|product| println!("{}", product))
-        .collect();
+    }
+
+    /// The smallest bag every draw could have been taken out of.
+    pub fn minimum_bag(&self) -> Bag {
+        let mut bag = Bag::default();
+        for draw in &self.draws {
+            for (color, _) in &draw.cubes {
+                let count = draw.count(color);
+                let least = bag.cubes.entry(color.clone()).or_default();
+                *least = (*least).max(count);
+            }
+        }
+        bag
+    }
+}
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Game>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        // Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        parse_lines(input, Game::parse)
+    }
+
+    fn part_one(games: &Self::Input) -> Result<Self::Answer> {
+        let bag = Bag::new([("red", 12), ("green", 13), ("blue", 14)]);
+
+        let result = games
+            .iter()
+            .filter(|game| game.possible_with(&bag))
+            .map(|game| game.id)
+            .sum();
+
+        Ok(result)
+    }
+
+    fn part_two(games: &Self::Input) -> Result<Self::Answer> {
+        let result = games
+            .iter()
+            .map(|game| {
+                let bag = game.minimum_bag();
+                ["red", "green", "blue"]
+                    .iter()
+                    .map(|color| bag.count(color))
+                    .product::<u32>()
+            })
+            .sum();
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_games() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue";
+
+        let games = Day2::parse(input).unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[1].id, 2);
+        assert_eq!(
+            games[0].draws[1],
+            Draw {
+                cubes: vec![
+                    ("red".to_string(), 1),
+                    ("green".to_string(), 2),
+                    ("blue".to_string(), 6)
+                ]
+            }
+        );
+        assert_eq!(
+            games[0].minimum_bag(),
+            Bag::new([("blue", 6), ("red", 4), ("green", 2)])
+        );
+    }
+
+    #[test]
+    fn resolve_example() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+        let games = Day2::parse(input).unwrap();
+
+        assert_eq!(Day2::part_one(&games).unwrap(), 8);
+        assert_eq!(Day2::part_two(&games).unwrap(), 2286);
+    }
+
+    #[test]
+    fn possible_with_other_colours() {
+        let games = Day2::parse("Game 7: 2 teal, 1 red; 3 teal").unwrap();
+
+        assert!(games[0].possible_with(&Bag::new([("teal", 3), ("red", 1)])));
+        assert!(!games[0].possible_with(&Bag::new([("teal", 2), ("red", 1)])));
+        assert!(!games[0].possible_with(&Bag::new([("teal", 3)])));
+    }
 
-    println!("{:?}", result.iter().sum::<u32>());
+    #[test]
+    fn parse_invalid_game() {
+        let input = "Game 1: 3 blue, 4 red
+Game 2: 1 blue, two green";
 
-    let result = result.iter().sum::<u32>();
+        let Err(Error::Parse(error)) = Day2::parse(input) else {
+            panic!("expected a parse error");
+        };
 
-    Ok(result)
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 17);
+        assert_eq!(error.text, "two");
+    }
 }
@@ -1,168 +1,151 @@
-use crate::utils::open_file;
-
-pub fn day3() -> Result<u32, std::io::Error> {
-    let contents = open_file("./inputs/3/input.txt")?;
-
-    // convert to matrix
-    let schematic_matrix: Vec<_> = contents
-        .lines()
-        .map(|line| line.chars().collect::<Vec<_>>())
-        .collect();
-
-    let mut schematic_numbers: Vec<u32> = vec![];
-    let mut i = 0;
-
-    while i < schematic_matrix.len() {
-        let line = schematic_matrix.get(i).unwrap();
-        let mut j = 0;
-        while j < line.len() {
-            let char = line.get(j).unwrap();
-            if char.is_ascii_digit() && number_is_valid(&schematic_matrix, i, j) {
-                let whole_number = find_whole_number(&schematic_matrix, i, &mut j);
-                schematic_numbers.push(whole_number);
-            }
-            j += 1;
-        }
-        i += 1;
-    }
+use std::ops::Range;
 
-    println!("{:?}", schematic_numbers);
+use crate::error::Result;
+use crate::solution::Solution;
+use crate::utils::{Grid, Position};
 
-    Ok(schematic_numbers.iter().sum::<u32>())
-}
+pub struct Day3;
 
-pub fn day3_part2() -> Result<u32, std::io::Error> {
-    let contents = open_file("./inputs/3/input.txt")?;
-
-    // convert to matrix
-    let schematic_matrix: Vec<_> = contents
-        .lines()
-        .map(|line| line.chars().collect::<Vec<_>>())
-        .collect();
-
-    let mut schematic_numbers: Vec<u32> = vec![];
-    let mut i = 0;
-
-    while i < schematic_matrix.len() {
-        let line = schematic_matrix.get(i).unwrap();
-        let mut j = 0;
-        while j < line.len() {
-            let char = line.get(j).unwrap();
-            if is_gear(*char) {
-                if let Some(number) = is_valid_gear(&schematic_matrix, i, j) {
-                    schematic_numbers.push(number);
-                }
-            }
-            j += 1;
-        }
-        i += 1;
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Grid<char>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Grid::parse(input, Some)
+    }
+
+    fn part_one(schematic: &Self::Input) -> Result<Self::Answer> {
+        let result = part_numbers(schematic)
+            .iter()
+            .filter(|number| {
+                number
+                    .cells()
+                    .flat_map(|cell| schematic.neighbours8(cell))
+                    .any(|neighbour| is_symbol(schematic[neighbour]))
+            })
+            .map(|number| number.value)
+            .sum();
+
+        Ok(result)
     }
 
-    println!("{:?}", schematic_numbers);
+    fn part_two(schematic: &Self::Input) -> Result<Self::Answer> {
+        let numbers = part_numbers(schematic);
+
+        let result = schematic
+            .iter()
+            .filter(|&(_, &tile)| is_gear(tile))
+            .filter_map(|(gear, _)| {
+                let gear_numbers: Vec<_> = numbers
+                    .iter()
+                    .filter(|number| number.touches(schematic, gear))
+                    .map(|number| number.value)
+                    .collect();
+
+                (gear_numbers.len() == 2).then(|| gear_numbers.iter().product::<u32>())
+            })
+            .sum();
 
-    Ok(schematic_numbers.iter().sum::<u32>())
+        Ok(result)
+    }
 }
 
-fn is_gear(char: char) -> bool {
-    char == '*'
+/// A run of digits on one row of the schematic.
+#[derive(Debug, PartialEq, Eq)]
+struct PartNumber {
+    row: usize,
+    columns: Range<usize>,
+    value: u32,
 }
 
-fn is_valid_gear(schematic_matrix: &Vec<Vec<char>>, i: usize, j: usize) -> Option<u32> {
-    let lower_i = i.saturating_sub(1);
-    let upper_i = schematic_matrix.len().saturating_sub(1).min(i + 1);
-    let lower_j = j.saturating_sub(1);
-    let upper_j = schematic_matrix
-        .get(i)
-        .unwrap()
-        .len()
-        .saturating_sub(1)
-        .min(j + 1);
-    let mut gear_numbers: Vec<u32> = vec![];
-    for i in lower_i..=upper_i {
-        let mut j = lower_j;
-        while j <= upper_j {
-            let char = schematic_matrix.get(i).unwrap().get(j).unwrap();
-            if char.is_ascii_digit() {
-                let whole_number = find_whole_number(schematic_matrix, i, &mut j);
-                gear_numbers.push(whole_number);
-            }
-            j += 1;
-        }
+impl PartNumber {
+    fn cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.columns.clone().map(|col| (self.row, col))
     }
 
-    print!("{:?} ", gear_numbers);
-
-    if gear_numbers.len() == 2 {
-        Some(gear_numbers.iter().product())
-    } else {
-        None
+    fn touches(&self, schematic: &Grid<char>, position: Position) -> bool {
+        schematic
+            .neighbours8(position)
+            .any(|neighbour| neighbour.0 == self.row && self.columns.contains(&neighbour.1))
     }
 }
 
-fn number_is_valid(schematic_matrix: &Vec<Vec<char>>, i: usize, j: usize) -> bool {
-    let lower_i = i.saturating_sub(1);
-    let upper_i = schematic_matrix.len().saturating_sub(1).min(i + 1);
-    let lower_j = j.saturating_sub(1);
-    let upper_j = schematic_matrix
-        .get(i)
-        .unwrap()
-        .len()
-        .saturating_sub(1)
-        .min(j + 1);
-    for i in lower_i..=upper_i {
-        for j in lower_j..=upper_j {
-            let char = schematic_matrix.get(i).unwrap().get(j).unwrap();
-            if is_symbol(*char) {
-                return true;
+fn part_numbers(schematic: &Grid<char>) -> Vec<PartNumber> {
+    let mut numbers = vec![];
+
+    for (row, tiles) in schematic.rows().enumerate() {
+        let mut col = 0;
+        while col < tiles.len() {
+            let start = col;
+            let mut value = 0;
+            while let Some(digit) = tiles.get(col).and_then(|tile| tile.to_digit(10)) {
+                value = value * 10 + digit;
+                col += 1;
+            }
+
+            if col > start {
+                numbers.push(PartNumber {
+                    row,
+                    columns: start..col,
+                    value,
+                });
+            } else {
+                col += 1;
             }
         }
     }
 
-    false
+    numbers
+}
+
+fn is_gear(char: char) -> bool {
+    char == '*'
 }
 
 fn is_symbol(char: char) -> bool {
     char != '.' && !char.is_alphanumeric() && !char.is_ascii_whitespace()
 }
 
-fn find_whole_number(schematic_matrix: &Vec<Vec<char>>, i: usize, j: &mut usize) -> u32 {
-    let mut number_start_index = *j;
-
-    let line = schematic_matrix.get(i).unwrap();
-
-    loop {
-        let temp_index = number_start_index.saturating_sub(1);
-        let char = line.get(temp_index).unwrap();
-        if char.is_ascii_digit() {
-            number_start_index = temp_index;
-        } else {
-            break;
-        }
-
-        if temp_index == 0 {
-            break;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_part_numbers() {
+        let input = "467..114..
+...*......
+..35...633";
+
+        let schematic = Day3::parse(input).unwrap();
+
+        assert_eq!(
+            part_numbers(&schematic),
+            vec![
+                PartNumber {
+                    row: 0,
+                    columns: 0..3,
+                    value: 467
+                },
+                PartNumber {
+                    row: 0,
+                    columns: 5..8,
+                    value: 114
+                },
+                PartNumber {
+                    row: 2,
+                    columns: 2..4,
+                    value: 35
+                },
+                PartNumber {
+                    row: 2,
+                    columns: 7..10,
+                    value: 633
+                },
+            ]
+        );
+        assert_eq!(Day3::part_one(&schematic).unwrap(), 467 + 35);
+        assert_eq!(Day3::part_two(&schematic).unwrap(), 467 * 35);
     }
-
-    loop {
-        let temp_index = *j + 1;
-        let char = line.get(temp_index).unwrap();
-        if char.is_ascii_digit() {
-            *j = temp_index;
-        } else {
-            break;
-        }
-
-        if temp_index == line.len() - 1 {
-            break;
-        }
-    }
-
-    let number: String = line
-        .iter()
-        .skip(number_start_index)
-        .take(*j - number_start_index + 1)
-        .collect();
-
-    number.parse::<u32>().unwrap()
 }
@@ -1,87 +1,90 @@
-use crate::utils::open_file;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::utils::{parse_header, parse_lines, parse_number};
 
-pub fn day4_part2() -> Result<u32, std::io::Error> {
-    let contents = open_file("./inputs/4/input.txt")?;
-    let mut cards: Vec<_> = contents
-        .lines()
-        .map(|line| {
-            let line_iter = line.split(':');
-            let cards = line_iter
-                .last()
-                .unwrap()
-                .split('|')
-                .map(|card| {
-                    card.trim()
-                        .split_ascii_whitespace()
-                        .map(|number| number.parse::<u32>().unwrap())
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>();
-            (cards[0].clone(), cards[1].clone(), 1)
-        })
-        .collect();
+pub struct Day4;
 
-    let mut index = 0;
-    loop {
-        let card = cards.get(index).unwrap().clone();
-        let wins = card.0.iter().filter(|&n| card.1.contains(n)).count();
+impl Solution for Day4 {
+    const DAY: u8 = 4;
 
-        for wins in (index + 1)..=index + wins {
-            if let Some((_, _, ref mut count)) = cards.get_mut(wins) {
-                *count += card.2;
-            }
-        }
+    type Input = Vec<(Vec<u32>, Vec<u32>)>;
+    type Answer = u32;
 
-        if index == cards.len() - 1 {
-            break;
-        }
+    fn parse(input: &str) -> Result<Self::Input> {
+        // every line looks like this:
+        // Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+        parse_lines(input, |line| {
+            let (_card, cards): (u32, _) = parse_header(line, "Card")?;
+            let (wining_numbers, game_numbers) = cards
+                .split_once('|')
+                .ok_or_else(|| Error::parse(line, &line[line.len()..], "expected `|`"))?;
+
+            let numbers = |text: &str| {
+                text.split_ascii_whitespace()
+                    .map(|number| parse_number(line, number))
+                    .collect::<Result<Vec<_>>>()
+            };
 
-        index += 1;
+            Ok((numbers(wining_numbers)?, numbers(game_numbers)?))
+        })
     }
 
-    let results: Vec<_> = cards.iter().map(|(_, _, count)| *count).collect();
+    fn part_one(cards: &Self::Input) -> Result<Self::Answer> {
+        let result = cards
+            .iter()
+            .map(|(wining_numbers, game_numbers)| {
+                let total_winning_numbers: Vec<u32> = wining_numbers
+                    .iter()
+                    .filter(|&wn| game_numbers.contains(wn))
+                    .cloned()
+                    .collect();
+                if total_winning_numbers.is_empty() {
+                    0
+                } else {
+                    1 << total_winning_numbers.len().checked_sub(1).unwrap()
+                }
+            })
+            .sum();
 
-    Ok(results.iter().sum::<u32>())
-}
+        Ok(result)
+    }
 
-pub fn day4_part1() -> Result<u32, std::io::Error> {
-    let contents = open_file("./inputs/4/input.txt")?;
-    // every line looks like this:
-    // Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-    let result: Vec<_> = contents
-        .lines()
-        .map(|line| {
-            let mut line_iter = line.split(':');
-            line_iter.next().unwrap();
-            let cards = line_iter
-                .next()
-                .unwrap()
-                .split('|')
-                .map(|card| {
-                    card.trim()
-                        .split_ascii_whitespace()
-                        .map(|number| number.parse::<u32>().unwrap())
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>();
-            (cards[0].clone(), cards[1].clone())
-        })
-        .map(|(wining_numbers, game_numbers)| {
-            let total_winning_numbers: Vec<u32> = wining_numbers
-                .iter()
-                .filter(|&wn| game_numbers.contains(wn))
-                .cloned()
-                .collect();
-            if total_winning_numbers.is_empty() {
-                0
-            } else {
-                1 << total_winning_numbers.len().checked_sub(1).unwrap()
+    fn part_two(cards: &Self::Input) -> Result<Self::Answer> {
+        let mut cards: Vec<_> = cards
+            .iter()
+            .map(|(wining_numbers, game_numbers)| (wining_numbers, game_numbers, 1))
+            .collect();
+
+        for index in 0..cards.len() {
+            let card = cards[index];
+            let wins = card.0.iter().filter(|&n| card.1.contains(n)).count();
+
+            for wins in (index + 1)..=index + wins {
+                if let Some((_, _, ref mut count)) = cards.get_mut(wins) {
+                    *count += card.2;
+                }
             }
-        })
-        .inspect(|line| {
-            dbg!(line);
-        })
-        .collect();
+        }
+
+        Ok(cards.iter().map(|(_, _, count)| *count).sum::<u32>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(result.iter().sum::<u32>())
+    #[test]
+    fn parse_invalid_card() {
+        let Err(Error::Parse(error)) = Day4::parse("Card 1: 4O | 1") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!((error.column, error.text.as_str()), (9, "4O"));
+        assert!(Day4::parse("Card 1: 4 | 1x").is_err());
+        assert_eq!(
+            Day4::parse("Card 1: 4 | 1 4").unwrap(),
+            vec![(vec![4], vec![1, 4])]
+        );
+    }
 }
@@ -1,57 +1,246 @@
-use crate::utils::open_file;
-use core::str::Lines;
 use std::ops::Range;
 
-#[derive(Debug)]
-struct Field {
-    // (index, value)
-    ranges: Range<u32>,
-}
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::utils::{blocks, parse_number, IntervalSet, Shift};
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Almanac;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Almanac::parse(input)
+    }
+
+    fn part_one(almanac: &Self::Input) -> Result<Self::Answer> {
+        let seeds = almanac
+            .seeds
+            .iter()
+            .map(|&seed| seed_range(seed, 1))
+            .collect::<Result<_>>()?;
+
+        almanac.lowest_location(seeds)
+    }
+
+    fn part_two(almanac: &Self::Input) -> Result<Self::Answer> {
+        let seeds = almanac
+            .seeds
+            .chunks(2)
+            .map(|pair| match *pair {
+                [start, len] => seed_range(start, len),
+                _ => Err(Error::NoSolution(
+                    "seeds do not come in `start length` pairs".to_string(),
+                )),
+            })
+            .collect::<Result<_>>()?;
 
-impl Field {
-    fn shift(&mut self, shift: &Shift) {
-        let (destination, source, len) = (shift.0, shift.1, shift.2);
+        almanac.lowest_location(seeds)
     }
 }
 
+fn seed_range(start: u64, len: u64) -> Result<Range<u64>> {
+    let end = start
+        .checked_add(len)
+        .ok_or_else(|| Error::NoSolution(format!("seeds {} + {} overflow u64", start, len)))?;
+
+    Ok(start..end)
+}
+
 #[derive(Debug)]
-struct Shift(u32, u32, u32);
+pub struct Almanac {
+    seeds: Vec<u64>,
+    groups: Vec<ShiftGroup>,
+}
 
-impl Shift {
-    fn parse(line: &str) -> Option<Self> {
-        if line.is_empty() {
-            return None;
+impl Almanac {
+    fn parse(input: &str) -> Result<Self> {
+        let blocks = blocks(input);
+        let Some(&(first, seeds)) = blocks.first() else {
+            return Err(Error::parse(input, input, "expected a `seeds:` line"));
+        };
+        let (_, numbers) = seeds
+            .split_once("seeds:")
+            .ok_or_else(|| Error::parse(seeds, seeds, "expected a `seeds:` line").on_line(first))?;
+        let seeds = numbers
+            .split_ascii_whitespace()
+            .map(|number| parse_number(seeds, number))
+            .collect::<Result<_>>()
+            .map_err(|e| e.on_line(first))?;
+
+        let mut groups: Vec<ShiftGroup> = vec![];
+        for &(first, block) in &blocks[1..] {
+            let from = groups.last().map_or("seed", |previous| &previous.to);
+            groups.push(ShiftGroup::parse(first, block, from)?);
         }
 
-        let mut line_iter = line.split_ascii_whitespace();
-        let destination = line_iter.next().unwrap().parse::<u32>().unwrap();
-        let source = line_iter.next().unwrap().parse::<u32>().unwrap();
-        let count = line_iter.next().unwrap().parse::<u32>().unwrap();
-        Some(Self(destination, source, count))
+        Ok(Self { seeds, groups })
+    }
+
+    /// Maps the seeds through every group in turn.
+    fn lowest_location(&self, seeds: IntervalSet) -> Result<u64> {
+        self.groups
+            .iter()
+            .fold(seeds, |values, group| values.map(&group.shifts))
+            .min()
+            .ok_or_else(|| Error::NoSolution("there are no seeds".to_string()))
+    }
+}
+
+fn parse_shift(line: &str) -> Result<Shift> {
+    let numbers = line
+        .split_ascii_whitespace()
+        .map(|number| parse_number(line, number))
+        .collect::<Result<Vec<u64>>>()?;
+
+    match numbers[..] {
+        [destination, source, len] => {
+            if destination.checked_add(len).is_none() || source.checked_add(len).is_none() {
+                return Err(Error::parse(line, line, "range overflows u64"));
+            }
+            Ok(Shift::new(destination, source, len))
+        }
+        _ => Err(Error::parse(
+            line,
+            line,
+            "expected `destination source length`",
+        )),
     }
 }
 
 #[derive(Debug)]
 struct ShiftGroup {
+    from: String,
+    to: String,
     shifts: Vec<Shift>,
 }
 
 impl ShiftGroup {
-    fn parse(lines: &mut Lines) -> Self {
-        let mut lines = lines.peekable();
-        while let Some(line) = lines.peek() {
-            if line.contains("map:") {
-                lines.next();
-                break;
-            }
-            lines.next();
+    /// Reads an `x-to-y map:` block starting on line `first`, where `x` has
+    /// to be `from`, the name the previous map ended on.
+    fn parse(first: usize, block: &str, from: &str) -> Result<Self> {
+        let header = block.lines().next().unwrap_or_default();
+        let (source, destination) = header
+            .strip_suffix(" map:")
+            .and_then(|names| names.split_once("-to-"))
+            .filter(|(source, destination)| !source.is_empty() && !destination.is_empty())
+            .ok_or_else(|| Error::parse(header, header, "expected `x-to-y map:`").on_line(first))?;
+        if source != from {
+            return Err(
+                Error::parse(header, source, format!("expected a `{}-to-` map", from))
+                    .on_line(first),
+            );
         }
 
-        let shifts: Vec<_> = lines
-            .map(Shift::parse)
-            .take_while(|shift| shift.is_some())
-            .map(|shift| shift.unwrap())
-            .collect();
-        Self { shifts }
+        let shifts = block
+            .lines()
+            .enumerate()
+            .skip(1)
+            .map(|(index, line)| parse_shift(line).map_err(|e| e.on_line(first + index)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            from: source.to_string(),
+            to: destination.to_string(),
+            shifts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn parse_typos() {
+        let input = EXAMPLE.replace("52 50 48", "52 5O 48");
+        let Err(Error::Parse(error)) = Day5::parse(&input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.text.as_str()), (5, "5O"));
+
+        assert!(Day5::parse(&EXAMPLE.replace("seeds: 79", "seeds: 7x9")).is_err());
+    }
+
+    #[test]
+    fn parse_overflowing_range() {
+        let input = EXAMPLE.replace("50 98 2", "50 18446744073709551615 2");
+        let Err(Error::Parse(error)) = Day5::parse(&input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            (error.line, error.message.as_str()),
+            (4, "range overflows u64")
+        );
+
+        let almanac = Day5::parse(&EXAMPLE.replace("55 13", "55 18446744073709551615")).unwrap();
+        assert!(Day5::part_two(&almanac).is_err());
+    }
+
+    #[test]
+    fn parse_headers() {
+        let input = EXAMPLE.replace("soil-to-fertilizer map:", "soil to fertilizer:");
+        let Err(Error::Parse(error)) = Day5::parse(&input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            (error.line, error.message.as_str()),
+            (7, "expected `x-to-y map:`")
+        );
+
+        let input = EXAMPLE.replace("water-to-light", "sand-to-light");
+        let Err(Error::Parse(error)) = Day5::parse(&input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            (error.line, error.text.as_str(), error.message.as_str()),
+            (18, "sand", "expected a `water-to-` map")
+        );
+    }
+
+    #[test]
+    fn resolve_example() {
+        let almanac = Day5::parse(EXAMPLE).unwrap();
+
+        assert_eq!(almanac.groups.len(), 7);
+        assert_eq!(Day5::part_one(&almanac).unwrap(), 35);
+        assert_eq!(Day5::part_two(&almanac).unwrap(), 46);
     }
 }
@@ -1,68 +1,112 @@
-use crate::utils::open_file;
-
-pub fn day6_part2() -> Result<u32, std::io::Error> {
-    let contents = open_file("./inputs/6/input.txt")?;
-    let times: Vec<_> = contents
-        .lines()
-        .next()
-        .unwrap()
-        .split_ascii_whitespace()
-        .skip(1)
-        .collect();
-    let distances: Vec<_> = contents
-        .lines()
-        .last()
-        .unwrap()
-        .split_ascii_whitespace()
-        .skip(1)
-        .collect();
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::utils::parse_number;
+
+pub struct Day6;
+
+#[derive(Debug)]
+pub struct Sheet {
+    times: Vec<u64>,
+    distances: Vec<u64>,
+}
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Sheet;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let times = input.lines().next().unwrap_or_default();
+        let distances = input.lines().last().unwrap_or_default();
+
+        let times = parse_row(times).map_err(|e| e.on_line(0))?;
+        let last_line = input.lines().count().saturating_sub(1);
+        let distances = parse_row(distances).map_err(|e| e.on_line(last_line))?;
+
+        Ok(Sheet { times, distances })
+    }
+
+    fn part_one(sheet: &Self::Input) -> Result<Self::Answer> {
+        let ways: Vec<_> = sheet
+            .times
+            .iter()
+            .zip(sheet.distances.iter())
+            .map(|(&t, &d)| {
+                (1..t)
+                    .map(|i| (t - i) * i)
+                    .filter(|&md| md > d)
+                    .collect::<Vec<_>>()
+            })
+            .map(|w| w.len())
+            .collect();
 
-    let total_time = times.join("").parse::<u64>().unwrap();
-    let total_distance = distances.join("").parse::<u64>().unwrap();
+        let result = ways.iter().map(|&w| w as u32).product::<u32>();
 
-    println!("{:?}", total_time);
-    println!("{:?}", total_distance);
+        Ok(result)
+    }
 
-    let ways = (1..total_time)
-        .map(|i| (total_time - i) * i)
-        .filter(|&md| md > total_distance)
-        .collect::<Vec<_>>();
+    fn part_two(sheet: &Self::Input) -> Result<Self::Answer> {
+        let join = |row: &[u64]| {
+            row.iter()
+                .map(u64::to_string)
+                .collect::<String>()
+                .parse::<u64>()
+                .map_err(|_| Error::NoSolution("the joined race does not fit in 64 bits".into()))
+        };
+        let total_time = join(&sheet.times)?;
+        let total_distance = join(&sheet.distances)?;
 
-    Ok(ways.len() as u32)
+        let ways = (1..total_time)
+            .map(|i| (total_time - i) * i)
+            .filter(|&md| md > total_distance)
+            .collect::<Vec<_>>();
+
+        Ok(ways.len() as u32)
+    }
 }
 
-pub fn day6_part1() -> Result<u32, std::io::Error> {
-    let contents = open_file("./inputs/6/input.txt")?;
-    let times: Vec<_> = contents
-        .lines()
-        .next()
-        .unwrap()
-        .split_ascii_whitespace()
-        .skip(1)
-        .map(|time| time.parse::<u32>().unwrap())
-        .collect();
-    let distances: Vec<_> = contents
-        .lines()
-        .last()
-        .unwrap()
+/// Reads the numbers of a `Label: 1 2 3` row.
+fn parse_row(line: &str) -> Result<Vec<u64>> {
+    let (_, numbers) = line
+        .split_once(':')
+        .ok_or_else(|| Error::parse(line, line, "expected a row of numbers"))?;
+
+    // stricter than `integers`, a typo like `4O` must not read as `4`
+    numbers
         .split_ascii_whitespace()
-        .skip(1)
-        .map(|time| time.parse::<u32>().unwrap())
-        .collect();
-
-    let ways: Vec<_> = times
-        .iter()
-        .zip(distances.iter())
-        .map(|(&t, &d)| {
-            (1..t)
-                .map(|i| (t - i) * i)
-                .filter(|&md| md > d)
-                .collect::<Vec<_>>()
-        })
-        .map(|w| w.len())
-        .collect();
-
-    let result = ways.iter().map(|&w| w as u32).product::<u32>();
-
-    Ok(result)
+        .map(|number| parse_number(line, number))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sheet() {
+        let input = "Time:      7  15   30
+Distance:  9  40  200";
+
+        let sheet = Day6::parse(input).unwrap();
+
+        assert_eq!(sheet.times, vec![7, 15, 30]);
+        assert_eq!(sheet.distances, vec![9, 40, 200]);
+        assert_eq!(Day6::part_one(&sheet).unwrap(), 288);
+        assert_eq!(Day6::part_two(&sheet).unwrap(), 71503);
+    }
+
+    #[test]
+    fn parse_invalid_sheet() {
+        let input = "Time:      7  15   30
+Distance:  9  4O  200";
+
+        let Err(Error::Parse(error)) = Day6::parse(input) else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 15);
+        assert_eq!(error.text, "4O");
+    }
 }
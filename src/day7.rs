@@ -1,22 +1,35 @@
-use crate::utils::open_file;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::utils::{parse_lines, parse_number};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
 
-pub fn day7() -> Result<u32, std::io::Error> {
-    let contents = open_file("./inputs/7/input.txt")?;
-    let mut hands: Vec<_> = contents.lines().map(Hand::parse).collect();
-    hands.sort();
-    hands.iter().for_each(|hand| println!("{}", hand));
+pub struct Day7;
 
-    let result = hands.iter().enumerate().fold(0, |acc, (index, hand)| {
-        acc + (hand.bid * (index as u32 + 1))
-    });
+impl Solution for Day7 {
+    const DAY: u8 = 7;
 
-    Ok(result)
+    type Input = Vec<Hand>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input, Hand::parse)
+    }
+
+    fn part_two(hands: &Self::Input) -> Result<Self::Answer> {
+        let mut hands = hands.clone();
+        hands.sort();
+
+        let result = hands.iter().enumerate().fold(0, |acc, (index, hand)| {
+            acc + (hand.bid * (index as u32 + 1))
+        });
+
+        Ok(result)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum SetType {
     Five,
     Four,
@@ -65,8 +78,8 @@ impl Ord for SetType {
     }
 }
 
-#[derive(Debug, Eq)]
-struct Hand {
+#[derive(Debug, Clone, Eq)]
+pub struct Hand {
     cards: Vec<u16>,
     bid: u32,
     set_type: SetType,
@@ -120,24 +133,37 @@ impl Ord for Hand {
 }
 
 impl Hand {
-    fn parse(line: &str) -> Self {
-        let mut line = line.split_ascii_whitespace();
+    fn parse(input: &str) -> Result<Self> {
+        let end = &input[input.len()..];
+        let mut line = input.split_ascii_whitespace();
 
-        let cards = line
+        let hand = line
             .next()
-            .unwrap()
-            .chars()
-            .map(|card| match card {
-                'A' => 14,
-                'K' => 13,
-                'Q' => 12,
-                'J' => 1,
-                'T' => 10,
-                _ => card.to_digit(10).unwrap() as u16,
+            .ok_or_else(|| Error::parse(input, end, "expected a hand"))?;
+        let cards = hand
+            .char_indices()
+            .map(|(index, card)| match card {
+                'A' => Ok(14),
+                'K' => Ok(13),
+                'Q' => Ok(12),
+                'J' => Ok(1),
+                'T' => Ok(10),
+                '2'..='9' => Ok(card.to_digit(10).unwrap() as u16),
+                _ => {
+                    let card = &hand[index..index + card.len_utf8()];
+                    Err(Error::parse(input, card, "invalid card"))
+                }
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>>>()?;
+        if cards.len() != 5 {
+            let message = format!("expected 5 cards, found {}", cards.len());
+            return Err(Error::parse(input, hand, message));
+        }
 
-        let bid = line.next().unwrap().parse::<u32>().unwrap();
+        let bid = line
+            .next()
+            .ok_or_else(|| Error::parse(input, end, "expected a bid"))?;
+        let bid = parse_number::<u32>(input, bid)?;
 
         let counts: HashMap<_, _> = cards
             .iter()
@@ -174,8 +200,6 @@ impl Hand {
 
         counts.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
 
-        println!("4 {:?}", counts);
-
         let set_type: SetType = match counts.len() {
             1 => match counts[0].1 {
                 5 => SetType::Five,
@@ -192,10 +216,48 @@ impl Hand {
             _ => SetType::HighCard,
         };
 
-        Self {
+        Ok(Self {
             cards,
             bid,
             set_type,
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_example() {
+        let input = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+        let hands = Day7::parse(input).unwrap();
+
+        assert_eq!(Day7::part_two(&hands).unwrap(), 5905);
+    }
+
+    #[test]
+    fn parse_invalid_card() {
+        let Err(Error::Parse(error)) = Hand::parse("32T3X 765") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(error.column, 5);
+        assert_eq!(error.text, "X");
+    }
+
+    #[test]
+    fn parse_missing_bid() {
+        let Err(Error::Parse(error)) = Hand::parse("32T3K") else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(error.column, 6);
+        assert_eq!(error.message, "expected a bid");
     }
 }
@@ -1,31 +1,49 @@
-use crate::utils::open_file;
-use num::integer::lcm;
-use std::collections::HashMap;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::utils::cycle::find_cycle;
+use crate::utils::math::crt_residues;
+use crate::utils::parse_record;
+use std::collections::{HashMap, HashSet};
 
-pub fn day8() -> Result<u64, std::io::Error> {
-    let contents = open_file("./inputs/8/input.txt")?;
+pub struct Day8;
 
-    let result = resolve_puzzle(&contents);
+impl Solution for Day8 {
+    const DAY: u8 = 8;
 
-    Ok(result)
-}
+    type Input = Map;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Map::parse(input)
+    }
 
-fn resolve_puzzle(input: &str) -> u64 {
-    let map = Map::parse(input);
-    let mut ghost_map = GhostMap::new(map);
+    fn part_one(map: &Self::Input) -> Result<Self::Answer> {
+        map.resolve().map(|steps| steps as u64)
+    }
 
-    ghost_map.resolve()
+    fn part_two(map: &Self::Input) -> Result<Self::Answer> {
+        map.check_nodes()?;
+        GhostMap::new(map.clone()).resolve()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Instructions(Vec<char>);
 
 impl Instructions {
-    fn parse(input: &str) -> Self {
-        Self(input.chars().collect())
+    fn parse(input: &str) -> Result<Self> {
+        if input.is_empty() {
+            return Err(Error::parse(input, input, "expected `L`/`R` instructions"));
+        }
+        if let Some((index, instruction)) = input.char_indices().find(|(_, c)| !"LR".contains(*c)) {
+            let instruction = &input[index..index + instruction.len_utf8()];
+            return Err(Error::parse(input, instruction, "invalid instruction"));
+        }
+
+        Ok(Self(input.chars().collect()))
     }
 
-    fn iter(&self) -> std::slice::Iter<char> {
+    fn iter(&self) -> std::slice::Iter<'_, char> {
         self.0.iter()
     }
 
@@ -38,72 +56,112 @@ impl Instructions {
 struct Node(String, (String, String));
 
 impl Node {
-    fn parse(input: &str) -> Self {
-        let mut parts = input.split(" = ");
-        let name = parts.next().unwrap().to_string();
-        let children = parts
-            .next()
-            .unwrap()
-            .trim_matches(|c| c == '(' || c == ')')
-            .split(", ")
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
-
-        Self(name, (children[0].clone(), children[1].clone()))
+    fn parse(input: &str) -> Result<Self> {
+        let (name, left, right) = parse_record(input)?;
+
+        Ok(Self(
+            name.to_string(),
+            (left.to_string(), right.to_string()),
+        ))
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Map {
+pub struct Map {
     instructions: Instructions,
     nodes: HashMap<String, (String, String)>,
 }
 
 impl Map {
-    fn parse(input: &str) -> Self {
-        let mut lines = input.lines();
-        let instructions = Instructions::parse(lines.next().unwrap());
+    fn parse(input: &str) -> Result<Self> {
+        let mut lines = input.lines().enumerate();
+        let (_, first) = lines.next().unwrap_or_default();
+        let instructions = Instructions::parse(first).map_err(|e| e.on_line(0))?;
+
+        let nodes = lines
+            .skip(1)
+            .try_fold(HashMap::new(), |mut acc, (index, line)| {
+                let node = Node::parse(line).map_err(|e| e.on_line(index))?;
+                acc.insert(node.0, node.1);
+                Ok(acc)
+            })?;
+
+        Ok(Self {
+            instructions,
+            nodes,
+        })
+    }
 
-        let nodes = lines.skip(1).map(Node::parse);
+    /// Steps from `AAA` to `ZZZ`. Standing on the same node at the same
+    /// instruction twice means the walk loops without reaching `ZZZ`.
+    fn resolve(&self) -> Result<u32> {
+        let mut current = "AAA";
+        let mut seen = HashSet::new();
+        let mut instructions = self.instructions.iter().enumerate().cycle();
+        let mut steps = 0;
 
-        let nodes = nodes.fold(HashMap::new(), |mut acc, node| {
-            acc.insert(node.0.clone(), node.1.clone());
-            acc
-        });
+        loop {
+            let (index, instruction) = instructions.next().expect("instructions are never empty");
+            if !seen.insert((current, index)) {
+                return Err(Error::NoSolution("`ZZZ` is never reached".to_string()));
+            }
 
-        Self {
-            instructions,
-            nodes,
+            let (left, right) = self.node(current)?;
+            current = match instruction {
+                'L' => left,
+                'R' => right,
+                _ => unreachable!("instructions are checked while parsing"),
+            };
+            steps += 1;
+
+            if current == "ZZZ" {
+                return Ok(steps);
+            }
         }
     }
 
-    fn resolve(&self) -> u32 {
-        let mut current_node = self.nodes.get("AAA").unwrap();
-
-        let Some(steps) =
-            self.instructions
-                .iter()
-                .cycle()
-                .enumerate()
-                .find_map(|(index, instruction)| {
-                    let next_step = match instruction {
-                        'L' => current_node.0.clone(),
-                        'R' => current_node.1.clone(),
-                        _ => panic!("Invalid instruction"),
-                    };
-
-                    if next_step == "ZZZ" {
-                        Some(index as u32 + 1)
-                    } else {
-                        current_node = self.nodes.get(&next_step).unwrap();
-                        None
-                    }
-                })
-        else {
-            panic!("No solution found");
-        };
+    fn node(&self, name: &str) -> Result<&(String, String)> {
+        self.nodes
+            .get(name)
+            .ok_or_else(|| Error::NoSolution(format!("node `{}` is not defined", name)))
+    }
 
-        steps
+    /// Makes sure every node we can step into is defined somewhere.
+    fn check_nodes(&self) -> Result<()> {
+        self.nodes
+            .values()
+            .flat_map(|(left, right)| [left, right])
+            .try_for_each(|name| self.node(name).map(|_| ()))
+    }
+}
+
+/// Where a ghost stands on the `Z` nodes once its walk starts repeating.
+#[derive(Debug, PartialEq, Eq)]
+struct GhostCycle {
+    /// Steps before the walk repeats, hits in there happen only once.
+    start: usize,
+    period: usize,
+    /// Every step below `start + period` that ends on a `Z` node.
+    hits: Vec<usize>,
+}
+
+impl GhostCycle {
+    fn at_goal(&self, steps: usize) -> bool {
+        if steps < self.start {
+            self.hits.contains(&steps)
+        } else {
+            let steps = self.start + (steps - self.start) % self.period;
+            self.hits.contains(&steps)
+        }
+    }
+
+    /// Step counts, modulo the period, that land on a `Z` node forever.
+    fn residues(&self) -> Vec<i64> {
+        self.hits
+            .iter()
+            .filter(|&&steps| steps >= self.start)
+            .map(|&steps| (steps % self.period) as i64)
+            .collect()
     }
 }
 
@@ -111,7 +169,6 @@ impl Map {
 struct GhostMap {
     map: Map,
     current_nodes: Vec<String>,
-    completed_nodes: HashMap<String, usize>,
 }
 
 impl GhostMap {
@@ -123,70 +180,113 @@ impl GhostMap {
             .map(|k| k.to_string())
             .collect();
         current_nodes.sort();
-        let completed_nodes = HashMap::new();
-        Self {
-            map,
-            current_nodes,
-            completed_nodes,
-        }
+        Self { map, current_nodes }
     }
 
-    fn step(&mut self, instruction: char) {
-        let current_nodes: Vec<_> = self
-            .current_nodes
-            .iter()
-            .filter(|node| !self.completed_nodes.contains_key(*node))
-            .map(|node| {
-                let next_node = match instruction {
-                    'L' => &self.map.nodes[node].0,
-                    'R' => &self.map.nodes[node].1,
-                    _ => panic!("Invalid instruction"),
-                };
-                next_node.to_string()
-            })
+    /// Follows one ghost until it is back on the same node at the same point
+    /// of the instructions.
+    fn ghost_cycle(&self, start: &str) -> GhostCycle {
+        let instructions = &self.map.instructions.0;
+        let cycle = find_cycle((start.to_string(), 0), |(node, index)| {
+            let (left, right) = &self.map.nodes[node];
+            let next = match instructions[*index] {
+                'L' => left,
+                'R' => right,
+                _ => unreachable!("instructions are checked while parsing"),
+            };
+            (next.to_string(), (index + 1) % instructions.len())
+        });
+        let hits = (0..cycle.start + cycle.period)
+            .filter(|&steps| cycle.state_at(steps).0.ends_with('Z'))
             .collect();
 
-        self.current_nodes = current_nodes;
+        GhostCycle {
+            start: cycle.start,
+            period: cycle.period,
+            hits,
+        }
     }
 
-    fn has_arrived(&mut self, steps: usize) -> bool {
-        let completed: Vec<_> = self
+    /// First step on which every ghost stands on a `Z` node.
+    fn resolve(&self) -> Result<u64> {
+        if self.current_nodes.is_empty() {
+            return Err(Error::NoSolution("there are no `A` nodes".to_string()));
+        }
+        let ghosts: Vec<_> = self
             .current_nodes
             .iter()
-            .filter(|node| node.ends_with('Z'))
-            .filter(|node| !self.completed_nodes.contains_key(*node))
+            .map(|node| self.ghost_cycle(node))
             .collect();
 
-        for node in completed {
-            self.completed_nodes.insert(node.to_string(), steps);
+        // before every ghost is looping, only walking them tells
+        let settled = ghosts.iter().map(|ghost| ghost.start).max().unwrap_or(0);
+        if let Some(steps) = (0..settled).find(|&steps| ghosts.iter().all(|g| g.at_goal(steps))) {
+            return Ok(steps as u64);
         }
 
-        self.current_nodes.is_empty()
-    }
-
-    fn resolve(&mut self) -> u64 {
-        let instructions = self.map.instructions.clone();
-        let mut intruction = instructions.iter().cycle().enumerate();
-        loop {
-            let (step, next_instruction) = intruction.next().unwrap();
-            if self.has_arrived(step) {
-                break;
-            }
-
-            self.step(*next_instruction);
-        }
+        let residues: Vec<_> = ghosts.iter().map(|ghost| ghost.residues()).collect();
+        let congruences: Vec<_> = ghosts
+            .iter()
+            .zip(&residues)
+            .map(|(ghost, residues)| (residues.as_slice(), ghost.period as i64))
+            .collect();
+        let (solutions, lcm) = crt_residues(&congruences).ok_or_else(|| {
+            Error::NoSolution("the ghosts never stand on `Z` nodes together".to_string())
+        })?;
 
-        dbg!(self.completed_nodes.clone());
+        // solutions repeat every `lcm` steps, lift them past the prefixes
+        let settled = settled as i64;
+        let steps = solutions
+            .iter()
+            .map(|&solution| {
+                let behind = (settled - solution).max(0);
+                solution + (behind + lcm - 1) / lcm * lcm
+            })
+            .min()
+            .expect("crt_residues returns at least one solution");
 
-        self.completed_nodes
-            .values()
-            .fold(1, |acc, &steps| lcm(acc, steps as u64))
+        Ok(steps as u64)
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn resolve_part_one() {
+        let input = "LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(Day8::part_one(&Day8::parse(input).unwrap()).unwrap(), 6);
+
+        let input = "RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(Day8::part_one(&Day8::parse(input).unwrap()).unwrap(), 2);
+    }
+
+    #[test]
+    fn resolve_unreachable_zzz() {
+        let input = "LR
+
+AAA = (BBB, AAA)
+BBB = (AAA, BBB)
+ZZZ = (ZZZ, ZZZ)";
+
+        let result = Day8::part_one(&Day8::parse(input).unwrap());
+
+        assert!(matches!(result, Err(Error::NoSolution(_))));
+    }
+
     #[test]
     fn test_resolve() {
         let input = "LLR
@@ -194,7 +294,7 @@ mod tests {
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(resolve_puzzle(input), 6);
+        assert_eq!(Day8::part_two(&Day8::parse(input).unwrap()).unwrap(), 6);
     }
 
     #[test]
@@ -208,14 +308,14 @@ DDD = (DDD, DDD)
 EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(resolve_puzzle(input), 2);
+        assert_eq!(Day8::part_two(&Day8::parse(input).unwrap()).unwrap(), 2);
     }
 
     #[test]
     fn test_parse_intructions() {
         let input = "RL";
 
-        let instructions = Instructions::parse(input);
+        let instructions = Instructions::parse(input).unwrap();
 
         let expected_instructions = Instructions(vec!['R', 'L']);
 
@@ -226,7 +326,7 @@ ZZZ = (ZZZ, ZZZ)";
     fn parse_node() {
         let input = "AAA = (BBB, CCC)";
 
-        let node = Node::parse(input);
+        let node = Node::parse(input).unwrap();
 
         let expected_node = Node("AAA".to_string(), ("BBB".to_string(), "CCC".to_string()));
 
@@ -240,7 +340,7 @@ ZZZ = (ZZZ, ZZZ)";
 AAA = (BBB, CCC)
 CCC = (ZZZ, AAA)";
 
-        let map = Map::parse(input);
+        let map = Map::parse(input).unwrap();
 
         let expected_map = Map {
             instructions: Instructions(vec!['R', 'L']),
@@ -267,7 +367,7 @@ CCC = (ZZZ, AAA)";
 22Z = (22B, 22B)
 11B = (XXX, 11Z)
 XXX = (XXX, XXX)";
-        let map = Map::parse(input);
+        let map = Map::parse(input).unwrap();
 
         let ghost_map = GhostMap::new(map);
 
@@ -278,55 +378,76 @@ XXX = (XXX, XXX)";
     }
 
     #[test]
-    fn test_ghost_map_step() {
+    fn test_ghost_cycle() {
         let input = "LR
 
 11A = (11B, XXX)
+11B = (XXX, 11Z)
 11Z = (11B, XXX)
 22A = (22B, XXX)
 22B = (22C, 22C)
 22C = (22Z, 22Z)
 22Z = (22B, 22B)
-11B = (XXX, 11Z)
 XXX = (XXX, XXX)";
 
-        let map = Map::parse(input);
-        let mut ghost_map = GhostMap::new(map);
-
-        ghost_map.step('L');
+        let map = Map::parse(input).unwrap();
+        let ghost_map = GhostMap::new(map);
 
         assert_eq!(
-            ghost_map.current_nodes,
-            vec!["11B".to_string(), "22B".to_string()]
+            ghost_map.ghost_cycle("11A"),
+            GhostCycle {
+                start: 1,
+                period: 2,
+                hits: vec![2]
+            }
+        );
+        assert_eq!(
+            ghost_map.ghost_cycle("22A"),
+            GhostCycle {
+                start: 1,
+                period: 6,
+                hits: vec![3, 6]
+            }
         );
     }
 
     #[test]
-    fn test_ghost_map_step_and_arrive() {
-        let input = "LR
+    fn test_ghost_offsets() {
+        // 11A reaches 11Z after 2 steps then every 3, 22A after 1 then every 2:
+        // an lcm of the first arrivals would answer 2
+        let input = "L
 
-11A = (11Z, XXX)
-11Z = (11B, XXX)
+11A = (11B, XXX)
+11B = (11Z, XXX)
+11Z = (11C, XXX)
+11C = (11D, XXX)
+11D = (11Z, XXX)
 22A = (22Z, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-11B = (XXX, 11Z)
+22Z = (22B, XXX)
+22B = (22Z, XXX)
 XXX = (XXX, XXX)";
 
-        let map = Map::parse(input);
-        let mut ghost_map = GhostMap::new(map);
+        let result = Day8::part_two(&Day8::parse(input).unwrap()).unwrap();
 
-        ghost_map.step('L');
+        assert_eq!(result, 5);
+    }
 
-        assert_eq!(
-            ghost_map.current_nodes,
-            vec!["11Z".to_string(), "22Z".to_string()]
-        );
+    #[test]
+    fn test_ghosts_never_meet() {
+        let input = "L
 
-        assert!(!ghost_map.has_arrived(1));
-        ghost_map.step('R');
-        assert!(ghost_map.has_arrived(2));
+11A = (11Z, XXX)
+11Z = (11B, XXX)
+11B = (11Z, XXX)
+22A = (22B, XXX)
+22B = (22Z, XXX)
+22Z = (22C, XXX)
+22C = (22Z, XXX)
+XXX = (XXX, XXX)";
+
+        let result = Day8::part_two(&Day8::parse(input).unwrap());
+
+        assert!(matches!(result, Err(Error::NoSolution(_))));
     }
 
     #[test]
@@ -342,10 +463,10 @@ XXX = (XXX, XXX)";
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
 
-        let map = Map::parse(input);
-        let mut ghost_map = GhostMap::new(map);
+        let map = Map::parse(input).unwrap();
+        let ghost_map = GhostMap::new(map);
 
-        let steps = ghost_map.resolve();
+        let steps = ghost_map.resolve().unwrap();
 
         assert_eq!(steps, 6);
     }
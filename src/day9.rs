@@ -1,64 +1,86 @@
-use crate::utils::open_file;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+use crate::utils::{parse_lines, parse_number};
 
-pub fn day9() -> Result<i64, std::io::Error> {
-    let contents = open_file("./inputs/9/input.txt")?;
+pub struct Day9;
 
-    let result = resolve_puzzle_2(&contents);
+impl Solution for Day9 {
+    const DAY: u8 = 9;
 
-    Ok(result)
-}
-
-fn resolve_puzzle(input: &str) -> i64 {
-    let mut readings = input.lines().map(Reading::parse).collect::<Vec<_>>();
-    let next_readings = readings
-        .iter_mut()
-        .map(|reading| {
-            reading.calc_diffs();
-            reading.next()
-        })
-        // .inspect(|next_reading| println!("next reading: {}", next_reading))
-        .collect::<Vec<_>>();
+    type Input = Vec<Reading>;
+    type Answer = i64;
 
-    next_readings.iter().sum()
-}
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input, Reading::parse)
+    }
 
-fn resolve_puzzle_2(input: &str) -> i64 {
-    let mut readings = input.lines().map(Reading::parse).collect::<Vec<_>>();
-    let prev_readings = readings
-        .iter_mut()
-        .map(|reading| {
-            reading.calc_diffs();
-            reading.prev()
-        })
-        // .inspect(|prev_reading| println!("result: {}", prev_reading))
-        .collect::<Vec<_>>();
+    fn part_one(readings: &Self::Input) -> Result<Self::Answer> {
+        let mut readings = readings.clone();
+        let next_readings = readings
+            .iter_mut()
+            .map(|reading| {
+                reading.calc_diffs()?;
+                Ok(reading.next())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(next_readings.iter().sum())
+    }
 
-    prev_readings.iter().sum()
+    fn part_two(readings: &Self::Input) -> Result<Self::Answer> {
+        let mut readings = readings.clone();
+        let prev_readings = readings
+            .iter_mut()
+            .map(|reading| {
+                reading.calc_diffs()?;
+                Ok(reading.prev())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(prev_readings.iter().sum())
+    }
 }
 
-struct Reading {
+#[derive(Clone)]
+pub struct Reading {
     values: Vec<i64>,
     diffs: Vec<Vec<i64>>,
 }
 
 impl Reading {
-    fn parse(input: &str) -> Self {
-        let values = input
+    fn parse(input: &str) -> Result<Self> {
+        let values: Vec<_> = input
             .split_ascii_whitespace()
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect();
+            .map(|s| parse_number::<i64>(input, s))
+            .collect::<Result<_>>()?;
 
-        Self {
+        if values.is_empty() {
+            return Err(Error::parse(input, input, "expected at least one value"));
+        }
+
+        Ok(Self {
             values,
             diffs: vec![],
-        }
+        })
     }
 
-    fn calc_diffs(&mut self) {
+    /// Fails when the differences run out before a row of zeros, there is
+    /// nothing to extrapolate from then. A lone reading is a constant.
+    fn calc_diffs(&mut self) -> Result<()> {
         let mut values = self.values.clone();
         let mut diffs = vec![values.clone()];
 
-        loop {
+        while !values.iter().all(|&d| d == 0) {
+            if values.len() < 2 {
+                if diffs.len() == 1 {
+                    break;
+                }
+                return Err(Error::NoSolution(format!(
+                    "the differences of {:?} never reach zero",
+                    self.values
+                )));
+            }
+
             let diff_row: Vec<i64> = values
                 .iter()
                 .zip(values.iter().skip(1))
@@ -66,15 +88,11 @@ impl Reading {
                 .collect();
 
             diffs.push(diff_row.clone());
-
-            if diff_row.iter().all(|&d| d == 0) {
-                break;
-            }
-
             values = diff_row;
         }
 
         self.diffs = diffs;
+        Ok(())
     }
 
     fn next(&self) -> i64 {
@@ -93,12 +111,15 @@ impl Reading {
     }
 }
 
+#[cfg(test)]
 mod tests {
+    use crate::solution::Solution;
+
     #[test]
     fn parse() {
         let line = "0 3 6 9 12 15";
 
-        let reading = super::Reading::parse(line);
+        let reading = super::Reading::parse(line).unwrap();
 
         assert_eq!(reading.values, vec![0, 3, 6, 9, 12, 15]);
     }
@@ -107,9 +128,9 @@ mod tests {
     fn diffs_1() {
         let line = "0 3 6 9 12 15";
 
-        let mut reading = super::Reading::parse(line);
+        let mut reading = super::Reading::parse(line).unwrap();
 
-        reading.calc_diffs();
+        reading.calc_diffs().unwrap();
 
         assert_eq!(
             reading.diffs,
@@ -125,9 +146,9 @@ mod tests {
     fn diffs_2() {
         let line = "1 3 6 10 15 21";
 
-        let mut reading = super::Reading::parse(line);
+        let mut reading = super::Reading::parse(line).unwrap();
 
-        reading.calc_diffs();
+        reading.calc_diffs().unwrap();
 
         assert_eq!(
             reading.diffs,
@@ -140,12 +161,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn diffs_never_zero() {
+        let mut reading = super::Reading::parse("1 2 4").unwrap();
+        assert!(matches!(
+            reading.calc_diffs(),
+            Err(super::Error::NoSolution(_))
+        ));
+
+        let mut reading = super::Reading::parse("5").unwrap();
+        reading.calc_diffs().unwrap();
+        assert_eq!((reading.next(), reading.prev()), (5, 5));
+
+        let readings = super::Day9::parse("0 3 6\n1 2 4").unwrap();
+        assert!(super::Day9::part_one(&readings).is_err());
+        assert!(super::Day9::part_two(&readings).is_err());
+    }
+
     #[test]
     fn next_reading_1() {
         let line = "0 3 6 9 12 15";
 
-        let mut reading = super::Reading::parse(line);
-        reading.calc_diffs();
+        let mut reading = super::Reading::parse(line).unwrap();
+        reading.calc_diffs().unwrap();
 
         assert_eq!(reading.next(), 18);
     }
@@ -154,8 +192,8 @@ mod tests {
     fn next_reading_2() {
         let line = "1 3 6 10 15 21";
 
-        let mut reading = super::Reading::parse(line);
-        reading.calc_diffs();
+        let mut reading = super::Reading::parse(line).unwrap();
+        reading.calc_diffs().unwrap();
 
         assert_eq!(reading.next(), 28);
     }
@@ -166,7 +204,7 @@ mod tests {
 1 3 6 10 15 21
 10 13 16 21 30 45";
 
-        let result = super::resolve_puzzle(contents);
+        let result = super::Day9::part_one(&super::Day9::parse(contents).unwrap()).unwrap();
 
         assert_eq!(result, 114);
     }
@@ -175,8 +213,8 @@ mod tests {
     fn prev_reading_1() {
         let line = "0 3 6 9 12 15";
 
-        let mut reading = super::Reading::parse(line);
-        reading.calc_diffs();
+        let mut reading = super::Reading::parse(line).unwrap();
+        reading.calc_diffs().unwrap();
 
         assert_eq!(reading.prev(), -3);
     }
@@ -185,8 +223,8 @@ mod tests {
     fn prev_reading_2() {
         let line = "1 3 6 10 15 21";
 
-        let mut reading = super::Reading::parse(line);
-        reading.calc_diffs();
+        let mut reading = super::Reading::parse(line).unwrap();
+        reading.calc_diffs().unwrap();
 
         assert_eq!(reading.prev(), 0);
     }
@@ -195,8 +233,8 @@ mod tests {
     fn prev_reading_3() {
         let line = "10 13 16 21 30 45";
 
-        let mut reading = super::Reading::parse(line);
-        reading.calc_diffs();
+        let mut reading = super::Reading::parse(line).unwrap();
+        reading.calc_diffs().unwrap();
 
         assert_eq!(reading.prev(), 5);
     }
@@ -207,7 +245,7 @@ mod tests {
 1 3 6 10 15 21
 10 13 16 21 30 45";
 
-        let result = super::resolve_puzzle_2(contents);
+        let result = super::Day9::part_two(&super::Day9::parse(contents).unwrap()).unwrap();
 
         assert_eq!(result, 2);
     }
@@ -216,9 +254,8 @@ mod tests {
     fn prev_reading_4() {
         let line = "10 19 48 117 252 484 864 1510 2714 5159 10327 21218 43546 87630 171255 323839 592306 1049131 1803090 3013315 4907320";
 
-        let mut reading = super::Reading::parse(line);
-        reading.calc_diffs();
-        println!("{:?}", reading.diffs);
+        let mut reading = super::Reading::parse(line).unwrap();
+        reading.calc_diffs().unwrap();
 
         assert_eq!(reading.prev(), 9);
     }
@@ -0,0 +1,190 @@
+use std::fmt;
+use std::io;
+
+use crate::solution::Part;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        context: String,
+        source: io::Error,
+    },
+    Parse(ParseError),
+    /// The input is well formed but has no answer, e.g. a maze without a
+    /// start tile.
+    NoSolution(String),
+    UnknownDay(u8),
+    Unsolved {
+        day: u8,
+        part: Part,
+    },
+}
+
+impl Error {
+    pub fn io(context: impl fmt::Display, source: io::Error) -> Self {
+        Self::Io {
+            context: context.to_string(),
+            source,
+        }
+    }
+
+    /// See [`ParseError::new`].
+    pub fn parse(line: &str, text: &str, message: impl Into<String>) -> Self {
+        Self::Parse(ParseError::new(line, text, message))
+    }
+
+    /// Records the zero based index of the line a parse error was found on.
+    pub fn on_line(self, index: usize) -> Self {
+        match self {
+            Self::Parse(error) => Self::Parse(ParseError {
+                line: index + 1,
+                ..error
+            }),
+            error => error,
+        }
+    }
+
+    pub fn in_day(self, day: u8) -> Self {
+        match self {
+            Self::Parse(error) => Self::Parse(ParseError {
+                day: Some(day),
+                ..error
+            }),
+            error => error,
+        }
+    }
+}
+
+/// `io::Error` is not `Clone`, a copy keeps its kind and message only.
+impl Clone for Error {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Io { context, source } => Self::Io {
+                context: context.clone(),
+                source: io::Error::new(source.kind(), source.to_string()),
+            },
+            Self::Parse(error) => Self::Parse(error.clone()),
+            Self::NoSolution(reason) => Self::NoSolution(reason.clone()),
+            Self::UnknownDay(day) => Self::UnknownDay(*day),
+            Self::Unsolved { day, part } => Self::Unsolved {
+                day: *day,
+                part: *part,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { context, source } => write!(f, "{}: {}", context, source),
+            Self::Parse(error) => write!(f, "{}", error),
+            Self::NoSolution(reason) => write!(f, "no solution: {}", reason),
+            Self::UnknownDay(day) => write!(f, "day {} is not implemented", day),
+            Self::Unsolved { day, part } => {
+                write!(f, "day {} part {} is not implemented", day, part)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Malformed puzzle input, located down to the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u8>,
+    /// One based, zero when unknown.
+    pub line: usize,
+    /// One based, counted in chars.
+    pub column: usize,
+    pub text: String,
+    pub source_line: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// `text` has to be a slice of `line`, its position gives the column. An
+    /// empty slice at the end of the line points at missing input.
+    pub fn new(line: &str, text: &str, message: impl Into<String>) -> Self {
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(line.as_ptr() as usize)
+            .filter(|&offset| offset <= line.len() && line.is_char_boundary(offset))
+            .unwrap_or(0);
+
+        Self {
+            day: None,
+            line: 0,
+            column: line[..offset].chars().count() + 1,
+            text: text.to_string(),
+            source_line: line.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if !self.text.is_empty() {
+            write!(f, " `{}`", self.text)?;
+        }
+        write!(
+            f,
+            "\n  {}\n  {:>width$}",
+            self.source_line,
+            "^",
+            width = self.column
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_column() {
+        let line = "32T3X 765";
+
+        let error = ParseError::new(line, &line[4..5], "invalid card");
+
+        assert_eq!(error.column, 5);
+        assert_eq!(error.text, "X");
+    }
+
+    #[test]
+    fn parse_error_at_end_of_line() {
+        let line = "32T3K";
+
+        let error = ParseError::new(line, &line[line.len()..], "missing bid");
+
+        assert_eq!(error.column, 6);
+        assert_eq!(error.text, "");
+    }
+
+    #[test]
+    fn parse_error_display() {
+        let line = "32T3X 765";
+
+        let error = Error::parse(line, &line[4..5], "invalid card")
+            .on_line(2)
+            .in_day(7);
+
+        assert_eq!(
+            error.to_string(),
+            "day 7, line 3, column 5: invalid card `X`
+  32T3X 765
+      ^"
+        );
+    }
+}
@@ -1,6 +1,11 @@
 // ignore dead code warnings
 #![allow(dead_code)]
 
+mod answers;
+mod bench;
+mod cli;
+mod client;
+mod config;
 mod day1;
 mod day10;
 mod day11;
@@ -13,11 +18,154 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod error;
+mod runner;
+mod scaffold;
+mod solution;
+mod submit;
 mod utils;
 
-use day14::day14 as proccess;
+use cli::{Command, View, USAGE};
+use runner::Source;
+use std::fmt;
+use std::path::Path;
+use std::process;
+use std::time::SystemTime;
 
 fn main() {
-    let result = proccess().unwrap();
-    println!("{}", result);
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Run {
+            day,
+            part,
+            source,
+            view,
+        } => run(day, part, &source, &view),
+        Command::All { source } => println!("{}", runner::summary(&runner::run_all(&source))),
+        Command::Verify => {
+            let reports = runner::run_all(&Source::Input);
+            let verdicts = answers::verify(&reports);
+            println!("{}", answers::summary(&verdicts));
+
+            if verdicts.iter().any(|(_, verdict)| verdict.is_failure()) {
+                process::exit(1);
+            }
+        }
+        Command::Bench(options) => bench(&options),
+        Command::NewDay { day } => {
+            let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+            let changed =
+                scaffold::new_day(root, &utils::inputs_dir(), day).unwrap_or_else(|e| fail(e));
+            for path in changed {
+                println!("wrote {}", path.display());
+            }
+        }
+        Command::Fetch { day } => {
+            let path = utils::day_file(day, "input.txt");
+            let fetched =
+                config::Config::load().and_then(|config| client::fetch_input(&config, day, &path));
+            match fetched {
+                Ok(client::Fetched::Downloaded) => println!("wrote {}", path.display()),
+                Ok(client::Fetched::Cached) => println!("{} is already cached", path.display()),
+                Err(message) => fail(message),
+            }
+        }
+        Command::Submit { day, part } => submit(day, part),
+        Command::Calibrate(options) => {
+            let vocabulary = options.vocabulary().unwrap_or_else(|e| fail(e));
+            if options.explain {
+                let input = options.source.read(1).unwrap_or_else(|e| fail(e));
+                print!("{}", day1::explain(&input, &vocabulary));
+            } else {
+                let sum = day1::calibrate_source(&options.source, &vocabulary)
+                    .unwrap_or_else(|e| fail(e));
+                println!("{}", sum);
+            }
+        }
+        Command::Help => println!("{}", USAGE),
+    }
+}
+
+fn fail(message: impl fmt::Display) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
+}
+
+fn run(day: u8, part: solution::Part, source: &Source, view: &View) {
+    if *view == View::default() {
+        let answer = runner::run(day, part, source).unwrap_or_else(|e| fail(e));
+        println!("{}", answer);
+        return;
+    }
+
+    // stdin can only be read once, so the drawing and the answer share it
+    let puzzle = solution::find(day).unwrap_or_else(|| fail(error::Error::UnknownDay(day)));
+    let input = source.read(day).unwrap_or_else(|e| fail(e));
+    let nothing_to_draw = format!("day {} has nothing to draw", day);
+
+    if view.render {
+        let canvas = puzzle.render(part, &input).unwrap_or_else(|e| fail(e));
+        println!("{}", canvas.unwrap_or_else(|| fail(&nothing_to_draw)));
+    }
+    if let Some(path) = &view.image {
+        let mut picture = puzzle
+            .picture(part, &input)
+            .unwrap_or_else(|e| fail(e))
+            .unwrap_or_else(|| fail(&nothing_to_draw));
+        if let Some(palette) = &view.palette {
+            picture = picture.palette(palette.clone());
+        }
+        if let Some(cell_size) = view.cell_size {
+            picture = picture.cell_size(cell_size);
+        }
+        picture.save(path).unwrap_or_else(|e| fail(e));
+    }
+
+    let answer = puzzle.solve(part, &input).unwrap_or_else(|e| fail(e));
+    println!("{}", answer);
+}
+
+fn bench(options: &bench::Options) {
+    // load the baseline first so a typo in its path does not waste a run
+    let baseline = options
+        .baseline
+        .as_ref()
+        .map(|path| bench::Baseline::load(path).unwrap_or_else(|e| fail(e)));
+    let measurements = bench::run(options).unwrap_or_else(|e| fail(e));
+
+    println!("{}", bench::summary(&measurements, baseline.as_ref()));
+
+    if let Some(path) = &options.json {
+        std::fs::write(path, bench::to_json(&measurements))
+            .unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    }
+    if let Some(baseline) = &baseline {
+        let regressions = bench::regressions(&measurements, baseline);
+        if !regressions.is_empty() {
+            eprintln!("{} regression(s)", regressions.len());
+            process::exit(1);
+        }
+    }
+}
+
+fn submit(day: u8, part: solution::Part) {
+    let answer = runner::run(day, part, &Source::Input).unwrap_or_else(|e| fail(e));
+    println!("submitting {} for day {} part {}", answer, day, part);
+
+    let config = config::Config::load().unwrap_or_else(|e| fail(e));
+    let cooldown = utils::inputs_dir().join(".submit-cooldown");
+    let outcome = submit::submit(&config, &cooldown, day, part, &answer, SystemTime::now())
+        .unwrap_or_else(|e| fail(e));
+    println!("{}", outcome);
+
+    if !outcome.is_success() {
+        process::exit(1);
+    }
 }
@@ -0,0 +1,169 @@
+use std::fmt::Write;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::solution::{self, Part, Puzzle};
+use crate::utils::{day_file, open_file};
+
+/// Where the puzzle input of a run comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `inputs/<day>/input.txt`
+    Input,
+    /// `inputs/<day>/test.txt`
+    Example,
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            Self::Input => Some(day_file(day, "input.txt")),
+            Self::Example => Some(day_file(day, "test.txt")),
+            Self::Path(path) => Some(path.clone()),
+            Self::Stdin => None,
+        }
+    }
+
+    pub fn read(&self, day: u8) -> Result<String> {
+        match self.path(day) {
+            Some(path) => open_file(&path).map_err(|e| Error::io(path.display(), e)),
+            None => {
+                let mut contents = String::new();
+                io::stdin()
+                    .read_to_string(&mut contents)
+                    .map_err(|e| Error::io("stdin", e))?;
+                Ok(contents)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Result<String>,
+    pub elapsed: Duration,
+}
+
+pub fn run(day: u8, part: Part, source: &Source) -> Result<String> {
+    let puzzle = solution::find(day).ok_or(Error::UnknownDay(day))?;
+    let input = source.read(day)?;
+
+    run_puzzle(puzzle, part, &input).answer
+}
+
+/// Reads every input once, a failed read is reported for both parts.
+pub fn run_all(source: &Source) -> Vec<Report> {
+    solution::PUZZLES
+        .iter()
+        .flat_map(|&puzzle| {
+            let input = source.read(puzzle.day());
+            Part::ALL.map(|part| match &input {
+                Ok(input) => run_puzzle(puzzle, part, input),
+                Err(error) => Report {
+                    day: puzzle.day(),
+                    part,
+                    answer: Err(error.clone()),
+                    elapsed: Duration::ZERO,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Only the solver is timed, reading the input is left out.
+fn run_puzzle(puzzle: &dyn Puzzle, part: Part, input: &str) -> Report {
+    let start = Instant::now();
+    let answer = puzzle.solve(part, input);
+
+    Report {
+        day: puzzle.day(),
+        part,
+        answer,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Lays the reports out as a table, one row per day and part, followed by
+/// the time spent on the answers found. Errors are cut to their first line
+/// to keep the table readable.
+pub fn summary(reports: &[Report]) -> String {
+    let answers: Vec<_> = reports
+        .iter()
+        .map(|report| match &report.answer {
+            Ok(answer) => answer.clone(),
+            Err(error) => {
+                let message = error.to_string();
+                format!("- ({})", message.lines().next().unwrap_or_default())
+            }
+        })
+        .collect();
+    let width = answers
+        .iter()
+        .map(|answer| answer.chars().count())
+        .chain(std::iter::once("Answer".len()))
+        .max()
+        .unwrap();
+
+    let mut table = String::new();
+    writeln!(table, "Day  Part  {:<width$}  Time", "Answer").unwrap();
+    for (report, answer) in reports.iter().zip(answers.iter()) {
+        let time = match report.answer {
+            Ok(_) => format!("{:.2?}", report.elapsed),
+            Err(_) => "-".to_string(),
+        };
+        writeln!(
+            table,
+            "{:>3}  {:>4}  {:<width$}  {}",
+            report.day, report.part, answer, time
+        )
+        .unwrap();
+    }
+
+    let total: Duration = reports
+        .iter()
+        .filter(|report| report.answer.is_ok())
+        .map(|report| report.elapsed)
+        .sum();
+    write!(table, "{:>9}  {:<width$}  {:.2?}", "", "Total", total).unwrap();
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_table() {
+        let reports = vec![
+            Report {
+                day: 9,
+                part: Part::One,
+                answer: Ok("1702218515".to_string()),
+                elapsed: Duration::from_micros(1500),
+            },
+            Report {
+                day: 9,
+                part: Part::Two,
+                answer: Err(Error::Unsolved {
+                    day: 9,
+                    part: Part::Two,
+                }),
+                elapsed: Duration::from_micros(500),
+            },
+        ];
+
+        let expected = "Day  Part  Answer                               Time
+  9     1  1702218515                           1.50ms
+  9     2  - (day 9 part 2 is not implemented)  -
+           Total                                1.50ms";
+
+        assert_eq!(summary(&reports), expected);
+    }
+}
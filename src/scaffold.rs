@@ -0,0 +1,299 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Skeleton of a new day, `{day}` is replaced by its number.
+const TEMPLATE: &str = r#"use crate::error::Result;
+use crate::solution::Solution;
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    const DAY: u8 = {day};
+
+    type Input = Vec<String>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.lines().map(String::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "part one is not solved yet"]
+    fn resolve_example() {
+        let input = "";
+
+        let input = Day{day}::parse(input).unwrap();
+
+        assert_eq!(Day{day}::part_one(&input).unwrap(), 0);
+    }
+}
+"#;
+
+pub fn source(day: u8) -> String {
+    TEMPLATE.replace("{day}", &day.to_string())
+}
+
+/// Adds `mod day<N>;` to `main.rs`, keeping the declarations in the order
+/// rustfmt sorts them.
+pub fn register_module(main: &str, day: u8) -> Result<String, String> {
+    let module = format!("day{}", day);
+    let modules: Vec<_> = main
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let name = line.strip_prefix("mod ")?.strip_suffix(';')?;
+            Some((index, name))
+        })
+        .collect();
+
+    if modules.iter().any(|&(_, name)| name == module) {
+        return Err(format!("`mod {};` is already declared", module));
+    }
+    let index = match modules.iter().find(|&&(_, name)| name > module.as_str()) {
+        Some(&(index, _)) => index,
+        None => modules.last().ok_or("no `mod` declarations found")?.0 + 1,
+    };
+
+    Ok(insert_line(main, index, &format!("mod {};", module)))
+}
+
+/// Adds the day to the `PUZZLES` registry, which is kept in calendar order.
+pub fn register_puzzle(solution: &str, day: u8) -> Result<String, String> {
+    let entry = |day: u8| format!("    &crate::day{}::Day{},", day, day);
+    let entries: Vec<_> = solution
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let day = line
+                .strip_prefix("    &crate::day")?
+                .split_once("::")?
+                .0
+                .parse::<u8>()
+                .ok()?;
+            (line == entry(day)).then_some((index, day))
+        })
+        .collect();
+
+    if entries.iter().any(|&(_, registered)| registered == day) {
+        return Err(format!("day {} is already registered", day));
+    }
+    let index = match entries.iter().find(|&&(_, registered)| registered > day) {
+        Some(&(index, _)) => index,
+        None => entries.last().ok_or("no registered puzzles found")?.0 + 1,
+    };
+
+    Ok(insert_line(solution, index, &entry(day)))
+}
+
+fn insert_line(text: &str, index: usize, line: &str) -> String {
+    let mut lines: Vec<_> = text.lines().collect();
+    lines.insert(index, line);
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Creates `src/day<N>.rs` and the day's input folder under `inputs`, then
+/// registers the module. When a step fails everything written before it is
+/// removed or restored, and existing input files are left alone.
+///
+/// Returns the files that were created or changed.
+pub fn new_day(root: &Path, inputs: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    let read =
+        |path: &Path| fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e));
+
+    let main_path = root.join("src").join("main.rs");
+    let solution_path = root.join("src").join("solution.rs");
+    let day_path = root.join("src").join(format!("day{}.rs", day));
+
+    if day_path.exists() {
+        return Err(format!("{} already exists", day_path.display()));
+    }
+    let main_original = read(&main_path)?;
+    let solution_original = read(&solution_path)?;
+    let main = register_module(&main_original, day)?;
+    let solution = register_puzzle(&solution_original, day)?;
+
+    let day_dir = inputs.join(day.to_string());
+    let created_dir = !day_dir.exists();
+    let mut changed = vec![];
+
+    let result = write_day(
+        &mut changed,
+        &day_path,
+        &day_dir,
+        [(main_path.clone(), main), (solution_path.clone(), solution)],
+        day,
+    );
+
+    if result.is_err() {
+        // best effort, the original error is the one worth reporting
+        for path in &changed {
+            let _ = if *path == main_path {
+                fs::write(path, &main_original)
+            } else if *path == solution_path {
+                fs::write(path, &solution_original)
+            } else {
+                fs::remove_file(path)
+            };
+        }
+        if created_dir {
+            let _ = fs::remove_dir(&day_dir);
+        }
+    }
+
+    result.map(|()| changed)
+}
+
+/// The writes of [`new_day`], every path is pushed to `changed` as soon as
+/// it is touched so a failure can be undone.
+fn write_day(
+    changed: &mut Vec<PathBuf>,
+    day_path: &Path,
+    day_dir: &Path,
+    registrations: [(PathBuf, String); 2],
+    day: u8,
+) -> Result<(), String> {
+    let write_error = |path: &Path, e: std::io::Error| format!("{}: {}", path.display(), e);
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(day_path)
+        .map_err(|e| write_error(day_path, e))?;
+    changed.push(day_path.to_path_buf());
+    file.write_all(source(day).as_bytes())
+        .map_err(|e| write_error(day_path, e))?;
+
+    fs::create_dir_all(day_dir).map_err(|e| write_error(day_dir, e))?;
+    for name in ["input.txt", "test.txt"] {
+        let path = day_dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => changed.push(path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => (),
+            Err(e) => return Err(write_error(&path, e)),
+        }
+    }
+
+    for (path, contents) in registrations {
+        changed.push(path.clone());
+        fs::write(&path, contents).map_err(|e| write_error(&path, e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "mod cli;
+mod day1;
+mod day11;
+mod day14;
+mod day2;
+mod solution;
+
+fn main() {}
+";
+
+    const SOLUTION: &str = "pub static PUZZLES: &[&dyn Puzzle] = &[
+    &crate::day1::Day1,
+    &crate::day2::Day2,
+    &crate::day11::Day11,
+    &crate::day14::Day14,
+];
+";
+
+    #[test]
+    fn register_new_module() {
+        let main = register_module(MAIN, 12).unwrap();
+        let main = register_module(&main, 3).unwrap();
+
+        assert_eq!(
+            main,
+            "mod cli;
+mod day1;
+mod day11;
+mod day12;
+mod day14;
+mod day2;
+mod day3;
+mod solution;
+
+fn main() {}
+"
+        );
+        assert!(register_module(MAIN, 14).is_err());
+        assert!(register_module("fn main() {}", 12).is_err());
+    }
+
+    #[test]
+    fn register_new_puzzle() {
+        let solution = register_puzzle(SOLUTION, 12).unwrap();
+        let solution = register_puzzle(&solution, 15).unwrap();
+
+        assert_eq!(
+            solution,
+            "pub static PUZZLES: &[&dyn Puzzle] = &[
+    &crate::day1::Day1,
+    &crate::day2::Day2,
+    &crate::day11::Day11,
+    &crate::day12::Day12,
+    &crate::day14::Day14,
+    &crate::day15::Day15,
+];
+"
+        );
+        assert!(register_puzzle(SOLUTION, 11).is_err());
+    }
+
+    #[test]
+    fn scaffold_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let inputs = root.join("inputs");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(inputs.join("12")).unwrap();
+        fs::write(root.join("src/main.rs"), MAIN).unwrap();
+        fs::write(root.join("src/solution.rs"), SOLUTION).unwrap();
+        fs::write(inputs.join("12/input.txt"), "kept").unwrap();
+
+        let changed = new_day(&root, &inputs, 12).unwrap();
+        let again = new_day(&root, &inputs, 12);
+
+        let read = |path: &str| fs::read_to_string(root.join(path)).unwrap();
+        assert_eq!(changed.len(), 4);
+        assert_eq!(read("src/day12.rs"), source(12));
+        assert!(read("src/main.rs").contains("mod day12;\n"));
+        assert!(read("src/solution.rs").contains("&crate::day12::Day12,\n"));
+        assert_eq!(read("inputs/12/input.txt"), "kept");
+        assert_eq!(read("inputs/12/test.txt"), "");
+        assert!(again.is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn scaffold_rolls_back() {
+        let root = std::env::temp_dir().join(format!("aoc-rollback-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/main.rs"), MAIN).unwrap();
+        fs::write(root.join("src/solution.rs"), SOLUTION).unwrap();
+        // a file where the inputs directory should be makes the second step fail
+        fs::write(root.join("inputs"), "").unwrap();
+
+        assert!(new_day(&root, &root.join("inputs"), 12).is_err());
+
+        assert!(!root.join("src/day12.rs").exists());
+        assert_eq!(fs::read_to_string(root.join("src/main.rs")).unwrap(), MAIN);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::utils::image::Picture;
+use crate::utils::render::Canvas;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(Self::One),
+            2 => Some(Self::Two),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::One => f.pad("1"),
+            Self::Two => f.pad("2"),
+        }
+    }
+}
+
+/// A day of the calendar, split into parsing and the two puzzle parts.
+///
+/// Parts that have not been solved yet keep the default implementation and
+/// return [`Error::Unsolved`]. Days with a grid worth looking at can also
+/// draw it for `run --render` and `run --image`.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Answer: fmt::Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part_one(_input: &Self::Input) -> Result<Self::Answer> {
+        Err(Error::Unsolved {
+            day: Self::DAY,
+            part: Part::One,
+        })
+    }
+
+    fn part_two(_input: &Self::Input) -> Result<Self::Answer> {
+        Err(Error::Unsolved {
+            day: Self::DAY,
+            part: Part::Two,
+        })
+    }
+
+    /// The state `part` ends in, `None` for days without a drawing.
+    fn render(_input: &Self::Input, _part: Part) -> Option<Canvas> {
+        None
+    }
+
+    /// The same state as [`Solution::render`] as a picture to save.
+    fn picture(_input: &Self::Input, _part: Part) -> Option<Picture> {
+        None
+    }
+}
+
+/// Object safe view of a [`Solution`], so every day can live in one registry.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u8;
+
+    fn solve(&self, part: Part, input: &str) -> Result<String>;
+
+    fn render(&self, part: Part, input: &str) -> Result<Option<Canvas>>;
+
+    fn picture(&self, part: Part, input: &str) -> Result<Option<Picture>>;
+}
+
+impl<S> Puzzle for S
+where
+    S: Solution + Sync,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn solve(&self, part: Part, input: &str) -> Result<String> {
+        let input = S::parse(input).map_err(|e| e.in_day(S::DAY))?;
+        let answer = match part {
+            Part::One => S::part_one(&input),
+            Part::Two => S::part_two(&input),
+        };
+
+        answer
+            .map(|answer| answer.to_string())
+            .map_err(|e| e.in_day(S::DAY))
+    }
+
+    fn render(&self, part: Part, input: &str) -> Result<Option<Canvas>> {
+        let input = S::parse(input).map_err(|e| e.in_day(S::DAY))?;
+        Ok(<S as Solution>::render(&input, part))
+    }
+
+    fn picture(&self, part: Part, input: &str) -> Result<Option<Picture>> {
+        let input = S::parse(input).map_err(|e| e.in_day(S::DAY))?;
+        Ok(<S as Solution>::picture(&input, part))
+    }
+}
+
+/// Every implemented day, in calendar order. `new-day` adds entries here.
+pub static PUZZLES: &[&dyn Puzzle] = &[
+    &crate::day1::Day1,
+    &crate::day2::Day2,
+    &crate::day3::Day3,
+    &crate::day4::Day4,
+    &crate::day5::Day5,
+    &crate::day6::Day6,
+    &crate::day7::Day7,
+    &crate::day8::Day8,
+    &crate::day9::Day9,
+    &crate::day10::Day10,
+    &crate::day11::Day11,
+    &crate::day14::Day14,
+];
+
+pub fn find(day: u8) -> Option<&'static dyn Puzzle> {
+    PUZZLES.iter().find(|puzzle| puzzle.day() == day).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzles_are_sorted_by_day() {
+        let days: Vec<_> = PUZZLES.iter().map(|puzzle| puzzle.day()).collect();
+
+        let mut expected = days.clone();
+        expected.sort();
+        expected.dedup();
+
+        assert_eq!(days, expected);
+    }
+
+    #[test]
+    fn find_puzzle() {
+        assert_eq!(find(8).map(|puzzle| puzzle.day()), Some(8));
+        assert!(find(12).is_none());
+    }
+
+    #[test]
+    fn solve_example() {
+        let input = "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+        let puzzle = find(9).unwrap();
+
+        assert_eq!(puzzle.solve(Part::One, input).unwrap(), "114");
+        assert_eq!(puzzle.solve(Part::Two, input).unwrap(), "2");
+    }
+
+    #[test]
+    fn solve_malformed_input() {
+        let input = "0 3 6 9 12 15
+1 3 6 x 15 21";
+
+        let puzzle = find(9).unwrap();
+
+        let Err(Error::Parse(error)) = puzzle.solve(Part::One, input) else {
+            panic!("expected a parse error");
+        };
+
+        assert_eq!(error.day, Some(9));
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 7);
+        assert_eq!(error.text, "x");
+    }
+}
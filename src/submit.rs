@@ -0,0 +1,342 @@
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::client::Client;
+use crate::config::Config;
+use crate::solution::Part;
+
+/// Used when a wrong answer does not say how long to wait.
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+/// What the site made of a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Wrong {
+        hint: Option<Hint>,
+        wait: Duration,
+    },
+    RateLimited {
+        wait: Duration,
+    },
+    /// The part was solved before, or part one is still open.
+    WrongLevel,
+    Unknown(String),
+}
+
+impl Outcome {
+    /// Reads the `<article>` of the response page.
+    pub fn parse(html: &str) -> Self {
+        let text = article_text(html);
+
+        if text.contains("That's the right answer") {
+            Self::Correct
+        } else if text.contains("That's not the right answer") {
+            let hint = if text.contains("too high") {
+                Some(Hint::TooHigh)
+            } else if text.contains("too low") {
+                Some(Hint::TooLow)
+            } else {
+                None
+            };
+            let wait = between(&text, "wait ", " before trying again")
+                .and_then(parse_wait)
+                .unwrap_or(DEFAULT_COOLDOWN);
+            Self::Wrong { hint, wait }
+        } else if text.contains("You gave an answer too recently") {
+            let wait = between(&text, "You have ", " left to wait")
+                .and_then(parse_wait)
+                .unwrap_or(DEFAULT_COOLDOWN);
+            Self::RateLimited { wait }
+        } else if text.contains("You don't seem to be solving the right level") {
+            Self::WrongLevel
+        } else {
+            Self::Unknown(text)
+        }
+    }
+
+    /// How long the site wants us to hold off before the next submission.
+    pub fn wait(&self) -> Option<Duration> {
+        match self {
+            Self::Wrong { wait, .. } | Self::RateLimited { wait } => Some(*wait),
+            _ => None,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Correct | Self::WrongLevel)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::Wrong { hint, wait } => {
+                write!(f, "wrong answer")?;
+                match hint {
+                    Some(Hint::TooHigh) => write!(f, ", too high")?,
+                    Some(Hint::TooLow) => write!(f, ", too low")?,
+                    None => (),
+                }
+                write!(f, ", wait {}s before the next try", wait.as_secs())
+            }
+            Self::RateLimited { wait } => write!(f, "rate limited, wait {}s", wait.as_secs()),
+            Self::WrongLevel => write!(f, "this part is already solved or not unlocked yet"),
+            Self::Unknown(text) => write!(f, "unrecognised response: {}", text),
+        }
+    }
+}
+
+/// Text of the first `<article>`, or the whole page, with tags removed and
+/// whitespace collapsed.
+fn article_text(html: &str) -> String {
+    let article = html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let (_, rest) = text.split_once(start)?;
+    Some(rest.split_once(end)?.0)
+}
+
+/// Understands the waits the site spells out, such as `37s`, `1m 5s`,
+/// `one minute` or `5 minutes`.
+fn parse_wait(text: &str) -> Option<Duration> {
+    let words: Vec<_> = text.split_whitespace().collect();
+    let mut seconds = 0;
+    let mut index = 0;
+
+    while index < words.len() {
+        let word = words[index];
+        let split = word
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(word.len());
+        let (number, unit) = match word.split_at(split) {
+            ("", _) => {
+                let number = match word {
+                    "one" | "a" | "an" => 1,
+                    "two" => 2,
+                    "three" => 3,
+                    "four" => 4,
+                    "five" => 5,
+                    "ten" => 10,
+                    _ => return None,
+                };
+                index += 1;
+                (number, *words.get(index)?)
+            }
+            (number, "") => {
+                index += 1;
+                (number.parse().ok()?, *words.get(index)?)
+            }
+            (number, unit) => (number.parse().ok()?, unit),
+        };
+        let unit = match unit.trim_end_matches('s') {
+            "" | "second" | "sec" => 1,
+            "m" | "minute" | "min" => 60,
+            "h" | "hour" => 60 * 60,
+            _ => return None,
+        };
+        seconds += number * unit;
+        index += 1;
+    }
+
+    (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+/// Reads when the next submission is allowed, kept in a small file holding
+/// a unix timestamp in seconds.
+fn load_cooldown(path: &Path) -> Result<Option<SystemTime>, String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => {
+            let seconds = contents
+                .trim()
+                .parse()
+                .map_err(|_| format!("{}: invalid timestamp", path.display()))?;
+            Ok(Some(UNIX_EPOCH + Duration::from_secs(seconds)))
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn save_cooldown(path: &Path, until: SystemTime) -> Result<(), String> {
+    // round up so we never come back a fraction of a second too early
+    let until = until.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = until.as_secs() + u64::from(until.subsec_nanos() > 0);
+    fs::write(path, format!("{}\n", seconds)).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Time left before `now` may submit again.
+pub fn cooldown_remaining(path: &Path, now: SystemTime) -> Result<Option<Duration>, String> {
+    let until = load_cooldown(path)?;
+    Ok(until
+        .and_then(|until| until.duration_since(now).ok())
+        .filter(|left| !left.is_zero()))
+}
+
+/// Posts `answer` unless the local cooldown in `cooldown` is still running,
+/// then extends the cooldown by whatever wait the site asked for.
+pub fn submit(
+    config: &Config,
+    cooldown: &Path,
+    day: u8,
+    part: Part,
+    answer: &str,
+    now: SystemTime,
+) -> Result<Outcome, String> {
+    if let Some(left) = cooldown_remaining(cooldown, now)? {
+        return Err(format!(
+            "the last submission was too recent, wait {}s",
+            left.as_secs_f64().ceil()
+        ));
+    }
+
+    let client = Client::new(config)?;
+    let level = part.to_string();
+    let page = client.post_form(
+        &format!("/day/{}/answer", day),
+        &[("level", &level), ("answer", answer)],
+    )?;
+    let outcome = Outcome::parse(&page);
+
+    if let Some(wait) = outcome.wait() {
+        save_cooldown(cooldown, now + wait)?;
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::{stub_config, stub_server};
+
+    const CORRECT: &str = "<main>
+<article><p>That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer to restoring snow operations. <a href=\"/2023/day/12#part2\">[Continue to Part Two]</a></p></article>
+</main>";
+
+    const TOO_HIGH: &str = "<main>
+<article><p>That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2023/about\">about page</a>, or you can ask for hints on the <a href=\"https://www.reddit.com/r/adventofcode/\" target=\"_blank\">subreddit</a>.  Please wait one minute before trying again. <a href=\"/2023/day/12\">[Return to Day 12]</a></p></article>
+</main>";
+
+    const RATE_LIMITED: &str = "<main>
+<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait. <a href=\"/2023/day/12\">[Return to Day 12]</a></p></article>
+</main>";
+
+    const WRONG_LEVEL: &str = "<main>
+<article><p>You don't seem to be solving the right level.  Did you already complete it? <a href=\"/2023/day/12\">[Return to Day 12]</a></p></article>
+</main>";
+
+    #[test]
+    fn parse_outcomes() {
+        assert_eq!(Outcome::parse(CORRECT), Outcome::Correct);
+        assert_eq!(
+            Outcome::parse(TOO_HIGH),
+            Outcome::Wrong {
+                hint: Some(Hint::TooHigh),
+                wait: Duration::from_secs(60)
+            }
+        );
+        assert_eq!(
+            Outcome::parse(&TOO_HIGH.replace("too high", "too low")),
+            Outcome::Wrong {
+                hint: Some(Hint::TooLow),
+                wait: Duration::from_secs(60)
+            }
+        );
+        assert_eq!(
+            Outcome::parse(RATE_LIMITED),
+            Outcome::RateLimited {
+                wait: Duration::from_secs(65)
+            }
+        );
+        assert_eq!(Outcome::parse(WRONG_LEVEL), Outcome::WrongLevel);
+        assert_eq!(
+            Outcome::parse("<article><p>Something <em>else</em></p></article>"),
+            Outcome::Unknown("Something else".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_waits() {
+        assert_eq!(parse_wait("37s"), Some(Duration::from_secs(37)));
+        assert_eq!(parse_wait("1m 5s"), Some(Duration::from_secs(65)));
+        assert_eq!(parse_wait("one minute"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_wait("5 minutes"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_wait("a while"), None);
+    }
+
+    #[test]
+    fn submit_with_cooldown() {
+        let (base_url, server) = stub_server(vec![(200, TOO_HIGH), (200, CORRECT)]);
+        let config = stub_config(base_url);
+        let cooldown = std::env::temp_dir().join(format!("aoc-cooldown-{}", std::process::id()));
+        let _ = fs::remove_file(&cooldown);
+        let now = UNIX_EPOCH + Duration::from_secs(1_701_388_800);
+
+        let first = submit(&config, &cooldown, 12, Part::One, "42", now);
+        let too_soon = submit(
+            &config,
+            &cooldown,
+            12,
+            Part::One,
+            "41",
+            now + Duration::from_secs(30),
+        );
+        let later = submit(
+            &config,
+            &cooldown,
+            12,
+            Part::One,
+            "41",
+            now + Duration::from_secs(60),
+        );
+        let requests = server.join().unwrap();
+
+        assert_eq!(
+            first,
+            Ok(Outcome::Wrong {
+                hint: Some(Hint::TooHigh),
+                wait: Duration::from_secs(60)
+            })
+        );
+        assert_eq!(
+            too_soon,
+            Err("the last submission was too recent, wait 30s".to_string())
+        );
+        assert_eq!(later, Ok(Outcome::Correct));
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("POST /2023/day/12/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=1&answer=42"));
+        assert!(requests[1].ends_with("\r\n\r\nlevel=1&answer=41"));
+
+        fs::remove_file(&cooldown).unwrap();
+    }
+}
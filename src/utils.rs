@@ -1,9 +0,0 @@
-use std::fs::File;
-use std::io::prelude::*;
-
-pub fn open_file(file_path: &str) -> Result<String, std::io::Error> {
-    let mut file = File::open(file_path)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    Ok(contents)
-}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Every state of a simulation up to the first repeated one: after `start`
+/// steps the states repeat every `period` steps.
+#[derive(Debug, Clone)]
+pub struct Cycle<S> {
+    pub start: usize,
+    pub period: usize,
+    states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /// The state after `steps` steps, without simulating them.
+    pub fn state_at(&self, steps: usize) -> &S {
+        let index = if steps < self.start {
+            steps
+        } else {
+            self.start + (steps - self.start) % self.period
+        };
+        &self.states[index]
+    }
+}
+
+/// Applies `step` from `initial` until a state comes back. Only terminates
+/// when the simulation has finitely many states.
+pub fn find_cycle<S, F>(initial: S, mut step: F) -> Cycle<S>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+
+    loop {
+        let next = step(&states[states.len() - 1]);
+        if let Some(&start) = seen.get(&next) {
+            return Cycle {
+                start,
+                period: states.len() - start,
+                states,
+            };
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+}
+
+/// The state after `steps` steps, fast-forwarding through the cycle.
+pub fn fast_forward<S, F>(initial: S, step: F, steps: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    find_cycle(initial, step).state_at(steps).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(x: &u32) -> u32 {
+        (x * x + 1) % 1000
+    }
+
+    #[test]
+    fn detect_cycle() {
+        // 3, 10, 101, 202, 805, 26, 677, 330, 901, 802, 205, 26, ...
+        let cycle = find_cycle(3, step);
+
+        assert_eq!((cycle.start, cycle.period), (5, 6));
+        assert_eq!(*cycle.state_at(0), 3);
+        assert_eq!(*cycle.state_at(5), 26);
+        assert_eq!(*cycle.state_at(11), 26);
+        assert_eq!(*cycle.state_at(1_000_000_001), 26);
+    }
+
+    #[test]
+    fn fast_forward_matches_simulation() {
+        let mut state = 7;
+        for steps in 0..200 {
+            assert_eq!(fast_forward(7, step, steps), state, "after {} steps", steps);
+            state = step(&state);
+        }
+
+        assert_eq!(find_cycle(0, |&x: &u32| x).period, 1);
+    }
+}
@@ -0,0 +1,281 @@
+use std::ops::{Index, IndexMut};
+
+use super::{Direction, Point};
+use crate::error::{Error, Result};
+
+/// `(row, column)`, both counted from the top left corner.
+pub type Position = (usize, usize);
+
+/// Offsets of all eight neighbours, row by row.
+const SURROUNDING: [Point; 8] = [
+    Point::new(-1, -1),
+    Point::new(-1, 0),
+    Point::new(-1, 1),
+    Point::new(0, -1),
+    Point::new(0, 1),
+    Point::new(1, -1),
+    Point::new(1, 0),
+    Point::new(1, 1),
+];
+
+/// A dense rectangular grid stored row by row.
+///
+/// Indexing with `grid[(row, col)]` panics outside the grid like a slice
+/// does, [`Grid::get`] and the neighbour iterators only ever return cells
+/// inside it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(height: usize, width: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Builds a grid from one char per cell, `parse` returns `None` for chars
+    /// that are not valid tiles. Every line must have the same length.
+    pub fn parse<F>(input: &str, mut parse: F) -> Result<Self>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        let mut cells = vec![];
+        let mut height = 0;
+
+        for (index, line) in input.lines().enumerate() {
+            let mut columns = 0;
+            for (offset, c) in line.char_indices() {
+                let cell = parse(c).ok_or_else(|| {
+                    let tile = &line[offset..offset + c.len_utf8()];
+                    Error::parse(line, tile, "invalid tile").on_line(index)
+                })?;
+                cells.push(cell);
+                columns += 1;
+            }
+            if columns != width {
+                let message = format!("expected {} columns, found {}", width, columns);
+                return Err(Error::parse(line, line, message).on_line(index));
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, col): Position) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[position.0 * self.width + position.1])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        if self.contains(position) {
+            Some(&mut self.cells[position.0 * self.width + position.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn swap(&mut self, a: Position, b: Position) {
+        assert!(
+            self.contains(a) && self.contains(b),
+            "position outside the grid"
+        );
+        self.cells
+            .swap(a.0 * self.width + a.1, b.0 * self.width + b.1);
+    }
+
+    /// Converts `point` to a position, `None` when it lies outside the grid.
+    pub fn position(&self, point: Point) -> Option<Position> {
+        point
+            .to_position()
+            .filter(|&position| self.contains(position))
+    }
+
+    /// Moves `position` by `delta`, `None` when that leaves the grid.
+    pub fn offset(&self, position: Position, delta: Point) -> Option<Position> {
+        self.position(Point::from(position) + delta)
+    }
+
+    /// The neighbour of `position` towards `direction`, if it is in the grid.
+    pub fn step(&self, position: Position, direction: Direction) -> Option<Position> {
+        self.offset(position, direction.offset())
+    }
+
+    /// The up to four cells sharing an edge with `position`, clockwise from
+    /// the one above it.
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        Direction::ALL
+            .iter()
+            .filter_map(move |&direction| self.step(position, direction))
+    }
+
+    /// The up to eight cells sharing an edge or a corner with `position`, row
+    /// by row.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        SURROUNDING
+            .iter()
+            .filter_map(move |&delta| self.offset(position, delta))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column outside the grid");
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position).expect("position outside the grid")
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        self.get_mut(position).expect("position outside the grid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse_grid() {
+        let grid = digits();
+
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(Grid::parse("", |c| c.to_digit(10)).unwrap().height(), 0);
+    }
+
+    #[test]
+    fn parse_invalid_grid() {
+        let Err(Error::Parse(error)) = Grid::parse("123\n4x6", |c| c.to_digit(10)) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.text, "x");
+
+        let Err(Error::Parse(error)) = Grid::parse("123\n45", |c| c.to_digit(10)) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "expected 3 columns, found 2");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = digits();
+
+        let corner: Vec<_> = grid.neighbours4((0, 0)).collect();
+        let edge: Vec<_> = grid.neighbours4((1, 1)).collect();
+        let all: Vec<_> = grid.neighbours8((0, 1)).collect();
+
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+        assert_eq!(edge, vec![(0, 1), (1, 2), (1, 0)]);
+        assert_eq!(all, vec![(0, 0), (0, 2), (1, 0), (1, 1), (1, 2)]);
+        assert_eq!(grid.step((0, 2), Direction::East), None);
+        assert_eq!(grid.step((0, 0), Direction::North), None);
+        assert_eq!(grid.step((0, 2), Direction::South), Some((1, 2)));
+        assert_eq!(grid.offset((0, 2), Point::new(1, -2)), Some((1, 0)));
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits();
+
+        let rows: Vec<_> = grid.rows().collect();
+        let columns: Vec<Vec<_>> = grid.columns().map(|col| col.copied().collect()).collect();
+
+        assert_eq!(rows, vec![&[1, 2, 3][..], &[4, 5, 6][..]]);
+        assert_eq!(columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+    }
+
+    #[test]
+    fn update_cells() {
+        let mut grid = digits();
+
+        grid[(0, 0)] = 9;
+        grid.swap((0, 0), (1, 2));
+        let doubled = grid.map(|&n| n * 2);
+
+        assert_eq!(grid.row(0), &[6, 2, 3]);
+        assert_eq!(grid.row(1), &[4, 5, 9]);
+        assert_eq!(doubled[(1, 2)], 18);
+        assert_eq!(
+            grid.iter()
+                .find(|&(_, &n)| n == 5)
+                .map(|(position, _)| position),
+            Some((1, 1))
+        );
+    }
+}
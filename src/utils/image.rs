@@ -0,0 +1,244 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use super::{Grid, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Reads `#rrggbb`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid colour `{}`, expected `#rrggbb`", text);
+        let hex = text
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6)
+            .ok_or_else(invalid)?;
+        let channel = |index: usize| {
+            hex.get(index..index + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+                .ok_or_else(invalid)
+        };
+
+        Ok(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Colours picked by index, each day documents what its indices mean.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette(Vec<Rgb>);
+
+impl Palette {
+    pub fn new(colors: Vec<Rgb>) -> Self {
+        assert!(!colors.is_empty(), "a palette needs at least one colour");
+        Self(colors)
+    }
+
+    /// Reads comma separated `#rrggbb` colours.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let colors = text
+            .split(',')
+            .map(|color| Rgb::parse(color.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(colors))
+    }
+
+    /// Indices past the end wrap around.
+    pub fn get(&self, index: usize) -> Rgb {
+        self.0[index % self.0.len()]
+    }
+}
+
+impl Default for Palette {
+    /// Dark background, then grey, gold, blue, green and red.
+    fn default() -> Self {
+        Self(vec![
+            Rgb(0x0f, 0x0f, 0x23),
+            Rgb(0x66, 0x66, 0x66),
+            Rgb(0xff, 0xd7, 0x00),
+            Rgb(0x3a, 0x7b, 0xd5),
+            Rgb(0x00, 0x99, 0x00),
+            Rgb(0xcc, 0x33, 0x33),
+        ])
+    }
+}
+
+/// A grid of palette indices that can be saved as an SVG or binary PPM
+/// image, each cell becoming a `cell_size` pixels wide square.
+#[derive(Debug, Clone)]
+pub struct Picture {
+    cells: Grid<usize>,
+    palette: Palette,
+    cell_size: usize,
+}
+
+impl Picture {
+    pub fn new<T, F>(grid: &Grid<T>, color: F) -> Self
+    where
+        F: FnMut(&T) -> usize,
+    {
+        Self {
+            cells: grid.map(color),
+            palette: Palette::default(),
+            cell_size: 8,
+        }
+    }
+
+    pub fn palette(self, palette: Palette) -> Self {
+        Self { palette, ..self }
+    }
+
+    pub fn cell_size(self, cell_size: usize) -> Self {
+        assert!(cell_size > 0, "cells need at least one pixel");
+        Self { cell_size, ..self }
+    }
+
+    /// Paints every cell in `positions` inside the picture with `color`.
+    pub fn layer<I>(mut self, positions: I, color: usize) -> Self
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        for position in positions {
+            if let Some(cell) = self.cells.get_mut(position) {
+                *cell = color;
+            }
+        }
+        self
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (
+            self.cells.width() * self.cell_size,
+            self.cells.height() * self.cell_size,
+        )
+    }
+
+    pub fn to_svg(&self) -> String {
+        let (width, height) = self.size();
+        let size = self.cell_size;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" shape-rendering=\"crispEdges\">\n"
+        );
+        for ((row, col), &color) in self.cells.iter() {
+            // writing to a String cannot fail
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"/>",
+                col * size,
+                row * size,
+                self.palette.get(color).hex()
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let (width, height) = self.size();
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        ppm.reserve(width * height * 3);
+
+        for row in self.cells.rows() {
+            let mut line = Vec::with_capacity(width * 3);
+            for &color in row {
+                let Rgb(r, g, b) = self.palette.get(color);
+                for _ in 0..self.cell_size {
+                    line.extend([r, g, b]);
+                }
+            }
+            for _ in 0..self.cell_size {
+                ppm.extend(&line);
+            }
+        }
+
+        ppm
+    }
+
+    /// Writes an SVG or PPM file depending on the extension of `path`.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("svg") => self.to_svg().into_bytes(),
+            Some("ppm") => self.to_ppm(),
+            _ => {
+                return Err(format!(
+                    "{}: unsupported image format, use .svg or .ppm",
+                    path.display()
+                ))
+            }
+        };
+
+        fs::write(path, contents).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picture() -> Picture {
+        let grid = Grid::parse("#.", |c| Some(c == '#')).unwrap();
+        Picture::new(&grid, |&wall| usize::from(wall))
+            .palette(Palette::parse("#000000, #ff8000").unwrap())
+            .cell_size(2)
+    }
+
+    #[test]
+    fn parse_palette() {
+        let palette = Palette::parse("#000000,#FF8000").unwrap();
+
+        assert_eq!(palette.get(1), Rgb(0xff, 0x80, 0x00));
+        assert_eq!(palette.get(2), Rgb(0, 0, 0));
+        assert!(Palette::parse("#00000").is_err());
+        assert!(Palette::parse("#00000g").is_err());
+        assert!(Palette::parse("#000000,").is_err());
+    }
+
+    #[test]
+    fn export_ppm() {
+        let black = [0, 0, 0];
+        let orange = [0xff, 0x80, 0x00];
+        let row = [orange, orange, black, black].concat();
+
+        let mut expected = b"P6\n4 2\n255\n".to_vec();
+        expected.extend(&row);
+        expected.extend(&row);
+
+        assert_eq!(picture().to_ppm(), expected);
+        assert_eq!(
+            picture().layer([(0, 1), (3, 3)], 1).to_ppm()[11..],
+            [orange; 8].concat()
+        );
+    }
+
+    #[test]
+    fn export_svg() {
+        let svg = picture().to_svg();
+
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"4\" height=\"2\"")
+        );
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"2\" height=\"2\" fill=\"#ff8000\"/>"));
+        assert!(svg.contains("<rect x=\"2\" y=\"0\" width=\"2\" height=\"2\" fill=\"#000000\"/>"));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn save_by_extension() {
+        let path = std::env::temp_dir().join(format!("aoc-picture-{}.ppm", std::process::id()));
+
+        picture().save(&path).unwrap();
+        let saved = fs::read(&path).unwrap();
+
+        assert_eq!(saved, picture().to_ppm());
+        assert!(picture().save(&path.with_extension("png")).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
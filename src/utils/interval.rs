@@ -0,0 +1,322 @@
+use std::ops::Range;
+
+/// Moves the values in `source..source + len` to start at `destination`.
+/// Both `source + len` and `destination + len` have to fit in a `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shift {
+    pub destination: u64,
+    pub source: u64,
+    pub len: u64,
+}
+
+impl Shift {
+    pub fn new(destination: u64, source: u64, len: u64) -> Self {
+        Self {
+            destination,
+            source,
+            len,
+        }
+    }
+
+    pub fn source(&self) -> Range<u64> {
+        self.source..self.source + self.len
+    }
+
+    /// Where `range` lands, it must lie inside [`Shift::source`].
+    fn apply(&self, range: &Range<u64>) -> Range<u64> {
+        let start = range.start - self.source + self.destination;
+        start..start + (range.end - range.start)
+    }
+}
+
+/// A set of `u64` stored as sorted half-open ranges. Ranges never overlap
+/// nor touch, adjacent ones are merged as they are added.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<Range<u64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&value))
+    }
+
+    pub fn insert(&mut self, range: Range<u64>) {
+        self.ranges.push(range);
+        self.normalize();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut left, mut right) = (self.ranges.iter(), other.ranges.iter());
+        let (mut a, mut b) = (left.next(), right.next());
+
+        while let (Some(x), Some(y)) = (a, b) {
+            let overlap = x.start.max(y.start)..x.end.min(y.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            if x.end < y.end {
+                a = left.next();
+            } else {
+                b = right.next();
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let mut others = other.ranges.iter().peekable();
+
+        for range in &self.ranges {
+            let mut start = range.start;
+            // ranges of `other` ending before this one cannot cut later ones
+            while others.next_if(|cut| cut.end <= start).is_some() {}
+            for cut in others.clone() {
+                if cut.start >= range.end {
+                    break;
+                }
+                if cut.start > start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Cuts the set at every edge of the shift sources. Each piece comes
+    /// with the index of the first shift covering it, `None` for values no
+    /// shift moves.
+    pub fn split(&self, shifts: &[Shift]) -> Vec<(Range<u64>, Option<usize>)> {
+        let mut pieces = vec![];
+
+        for range in &self.ranges {
+            let mut cuts: Vec<_> = shifts
+                .iter()
+                .flat_map(|shift| [shift.source, shift.source + shift.len])
+                .filter(|cut| range.contains(cut))
+                .chain([range.start, range.end])
+                .collect();
+            cuts.sort_unstable();
+            cuts.dedup();
+
+            for window in cuts.windows(2) {
+                let piece = window[0]..window[1];
+                let shift = shifts
+                    .iter()
+                    .position(|shift| shift.source().contains(&piece.start));
+                pieces.push((piece, shift));
+            }
+        }
+
+        pieces
+    }
+
+    /// Where the set lands once every value covered by one of `shifts` is
+    /// moved, the others staying in place.
+    pub fn map(&self, shifts: &[Shift]) -> Self {
+        self.split(shifts)
+            .into_iter()
+            .map(|(piece, shift)| match shift {
+                Some(index) => shifts[index].apply(&piece),
+                None => piece,
+            })
+            .collect()
+    }
+
+    /// Sorts the ranges, drops empty ones and merges overlapping or
+    /// adjacent ones.
+    fn normalize(&mut self) {
+        self.ranges.retain(|range| !range.is_empty());
+        self.ranges.sort_unstable_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+}
+
+impl FromIterator<Range<u64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<u64>>>(iter: I) -> Self {
+        let mut set = Self {
+            ranges: iter.into_iter().collect(),
+        };
+        set.normalize();
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Small xorshift generator, enough to build reproducible random sets.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+
+        fn range(&mut self) -> Range<u64> {
+            let start = self.below(48);
+            start..start + self.below(12)
+        }
+
+        fn set(&mut self) -> IntervalSet {
+            let count = self.below(5);
+            (0..count).map(|_| self.range()).collect()
+        }
+    }
+
+    fn values(set: &IntervalSet) -> BTreeSet<u64> {
+        set.ranges()
+            .iter()
+            .flat_map(|range| range.clone())
+            .collect()
+    }
+
+    fn is_normalized(set: &IntervalSet) -> bool {
+        set.ranges().iter().all(|range| !range.is_empty())
+            && set
+                .ranges()
+                .windows(2)
+                .all(|pair| pair[0].end < pair[1].start)
+    }
+
+    #[test]
+    fn merge_ranges() {
+        let set: IntervalSet = [5..8, 0..2, 2..3, 7..10, 12..12].into_iter().collect();
+
+        assert_eq!(set.ranges(), &[0..3, 5..10]);
+        assert_eq!(set.len(), 8);
+        assert_eq!(set.min(), Some(0));
+        assert!(set.contains(9));
+        assert!(!set.contains(3));
+        assert!(!set.contains(10));
+    }
+
+    #[test]
+    fn map_through_shifts() {
+        let set: IntervalSet = [79..93, 55..68].into_iter().collect();
+        let shifts = [Shift::new(50, 98, 2), Shift::new(52, 50, 48)];
+
+        assert_eq!(
+            set.split(&[Shift::new(0, 60, 5)]),
+            vec![
+                (55..60, None),
+                (60..65, Some(0)),
+                (65..68, None),
+                (79..93, None)
+            ]
+        );
+        assert_eq!(set.map(&shifts).ranges(), &[57..70, 81..95]);
+    }
+
+    #[test]
+    fn set_operations_match_brute_force() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..500 {
+            let (a, b) = (rng.set(), rng.set());
+            let (values_a, values_b) = (values(&a), values(&b));
+
+            let union = a.union(&b);
+            let intersection = a.intersection(&b);
+            let difference = a.difference(&b);
+
+            for set in [&a, &union, &intersection, &difference] {
+                assert!(is_normalized(set), "{:?}", set);
+            }
+            assert_eq!(a.len(), values_a.len() as u64);
+            assert_eq!(values(&union), &values_a | &values_b);
+            assert_eq!(values(&intersection), &values_a & &values_b);
+            assert_eq!(values(&difference), &values_a - &values_b);
+            assert!((0..64).all(|value| a.contains(value) == values_a.contains(&value)));
+        }
+    }
+
+    #[test]
+    fn shifts_match_brute_force() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..500 {
+            let set = rng.set();
+            let shifts: Vec<_> = (0..rng.below(4))
+                .map(|_| {
+                    let source = rng.range();
+                    Shift::new(rng.below(64), source.start, source.end - source.start)
+                })
+                .collect();
+
+            let expected: BTreeSet<_> = values(&set)
+                .into_iter()
+                .map(|value| {
+                    shifts
+                        .iter()
+                        .find(|shift| shift.source().contains(&value))
+                        .map_or(value, |shift| value - shift.source + shift.destination)
+                })
+                .collect();
+            let pieces = set.split(&shifts);
+            let mapped = set.map(&shifts);
+
+            assert!(is_normalized(&mapped), "{:?}", mapped);
+            assert_eq!(values(&mapped), expected);
+            assert_eq!(
+                pieces
+                    .iter()
+                    .flat_map(|(piece, _)| piece.clone())
+                    .collect::<BTreeSet<_>>(),
+                values(&set)
+            );
+        }
+    }
+}
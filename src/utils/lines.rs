@@ -0,0 +1,110 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// Large enough that huge inputs are read in few system calls.
+const BUFFER_SIZE: usize = 1 << 16;
+
+/// Reads lines one at a time so inputs never have to fit in memory, unlike
+/// [`super::open_file`]. Line endings, `\n` or `\r\n`, are stripped.
+///
+/// [`LineReader::next_line`] reuses one buffer for every line, iterating
+/// allocates a `String` per line instead.
+#[derive(Debug)]
+pub struct LineReader<R> {
+    reader: R,
+    buffer: String,
+    index: usize,
+}
+
+impl LineReader<BufReader<File>> {
+    /// Opens `path` for reading line by line.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Ok(Self::new(BufReader::with_capacity(BUFFER_SIZE, file)))
+    }
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            index: 0,
+        }
+    }
+
+    /// Number of lines read so far, which is the zero based index of the
+    /// next one.
+    pub fn lines_read(&self) -> usize {
+        self.index
+    }
+
+    /// The next line, borrowed until the following call.
+    pub fn next_line(&mut self) -> Option<io::Result<&str>> {
+        self.buffer.clear();
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                self.index += 1;
+                let line = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+                Some(Ok(line.strip_suffix('\r').unwrap_or(line)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for LineReader<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_line().map(|line| line.map(str::to_string))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Cursor;
+
+    #[test]
+    fn stream_lines() {
+        let mut reader = LineReader::new(Cursor::new("one\r\ntwo\n\nthree"));
+
+        assert_eq!(reader.next_line().unwrap().unwrap(), "one");
+        assert_eq!(reader.lines_read(), 1);
+
+        let rest: Vec<_> = reader.by_ref().collect::<io::Result<_>>().unwrap();
+
+        assert_eq!(rest, vec!["two", "", "three"]);
+        assert_eq!(reader.lines_read(), 4);
+        assert!(reader.next_line().is_none());
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut reader = LineReader::new(Cursor::new(b"ok\n\xff\xfe\n".to_vec()));
+
+        assert_eq!(reader.next_line().unwrap().unwrap(), "ok");
+        let error = reader.next_line().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_file() {
+        let path = std::env::temp_dir().join(format!("aoc-lines-{}.txt", std::process::id()));
+        fs::write(&path, "1 2 3\n4 5 6\n").unwrap();
+
+        let lines: Vec<_> = LineReader::open(&path)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(lines, vec!["1 2 3", "4 5 6"]);
+        assert!(LineReader::open(path.with_extension("missing")).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::VecDeque;
+
+/// One occurrence of a pattern, `start..end` being its byte range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: V,
+}
+
+/// Aho–Corasick automaton finding every occurrence of a set of patterns,
+/// overlapping ones included, in a single pass over the text.
+///
+/// Transitions are stored for every byte so each step is one lookup, which
+/// suits the small pattern sets puzzles use.
+#[derive(Debug, Clone)]
+pub struct Matcher<V> {
+    transitions: Vec<[usize; 256]>,
+    /// Patterns ending at each state, through suffix links included.
+    outputs: Vec<Vec<usize>>,
+    /// Length and value of every pattern.
+    patterns: Vec<(usize, V)>,
+}
+
+impl<V: Copy> Matcher<V> {
+    /// Empty patterns are ignored.
+    pub fn new<P, I>(patterns: I) -> Self
+    where
+        P: AsRef<[u8]>,
+        I: IntoIterator<Item = (P, V)>,
+    {
+        // state 0 is the root, no trie edge leads back to it so 0 also marks
+        // a missing edge until the suffix links fill them in
+        let mut transitions = vec![[0; 256]];
+        let mut outputs = vec![vec![]];
+        let mut lengths = vec![];
+
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                continue;
+            }
+            let mut state = 0;
+            for &byte in pattern {
+                if transitions[state][byte as usize] == 0 {
+                    transitions.push([0; 256]);
+                    outputs.push(vec![]);
+                    transitions[state][byte as usize] = transitions.len() - 1;
+                }
+                state = transitions[state][byte as usize];
+            }
+            outputs[state].push(lengths.len());
+            lengths.push((pattern.len(), value));
+        }
+
+        // breadth first, so the suffix link of a state is complete before
+        // its children need it
+        let mut suffix = vec![0; transitions.len()];
+        let mut queue: VecDeque<_> = transitions[0].iter().copied().filter(|&s| s != 0).collect();
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[suffix[state]].clone();
+            outputs[state].extend(inherited);
+            let fallbacks = transitions[suffix[state]];
+            for (child, fallback) in transitions[state].iter_mut().zip(fallbacks) {
+                if *child == 0 {
+                    *child = fallback;
+                } else {
+                    suffix[*child] = fallback;
+                    queue.push_back(*child);
+                }
+            }
+        }
+
+        Self {
+            transitions,
+            outputs,
+            patterns: lengths,
+        }
+    }
+
+    /// Every match in `text`, ordered by where it ends.
+    pub fn find_overlapping<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match<V>> + 'a {
+        text.bytes()
+            .enumerate()
+            .scan(0, move |state, (index, byte)| {
+                *state = self.transitions[*state][byte as usize];
+                Some((index + 1, *state))
+            })
+            .flat_map(move |(end, state)| {
+                self.outputs[state].iter().map(move |&pattern| {
+                    let (len, value) = self.patterns[pattern];
+                    Match {
+                        start: end - len,
+                        end,
+                        value,
+                    }
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(patterns: &[&str], text: &str) -> Vec<(usize, usize)> {
+        let mut matches: Vec<_> = patterns
+            .iter()
+            .enumerate()
+            .filter(|(_, pattern)| !pattern.is_empty())
+            .flat_map(|(index, pattern)| {
+                (0..=text.len().saturating_sub(pattern.len()))
+                    .filter(move |&start| text[start..].starts_with(pattern))
+                    .map(move |start| (start, index))
+            })
+            .collect();
+        matches.sort_by_key(|&(start, index)| (start + patterns[index].len(), index));
+        matches
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let matcher = Matcher::new([("one", 1), ("two", 2), ("eight", 8), ("8", 8)]);
+
+        let matches: Vec<_> = matcher.find_overlapping("xeightwone8").collect();
+
+        assert_eq!(
+            matches,
+            vec![
+                Match {
+                    start: 1,
+                    end: 6,
+                    value: 8
+                },
+                Match {
+                    start: 5,
+                    end: 8,
+                    value: 2
+                },
+                Match {
+                    start: 7,
+                    end: 10,
+                    value: 1
+                },
+                Match {
+                    start: 10,
+                    end: 11,
+                    value: 8
+                },
+            ]
+        );
+        assert_eq!(matcher.find_overlapping("").count(), 0);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let patterns = ["a", "ab", "bab", "bc", "bca", "c", "caa", "", "abab"];
+        let matcher = Matcher::new(patterns.iter().enumerate().map(|(i, p)| (p, i)));
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+
+        for _ in 0..300 {
+            let text: String = (0..seed % 20)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    ['a', 'b', 'c', 'd'][(seed % 4) as usize]
+                })
+                .collect();
+
+            let mut matches: Vec<_> = matcher
+                .find_overlapping(&text)
+                .map(|m| {
+                    assert_eq!(&text[m.start..m.end], patterns[m.value], "in {}", text);
+                    (m.start, m.value)
+                })
+                .collect();
+            matches.sort_by_key(|&(start, index)| (start + patterns[index].len(), index));
+
+            assert_eq!(matches, brute_force(&patterns, &text), "in {}", text);
+        }
+    }
+}
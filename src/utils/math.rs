@@ -0,0 +1,179 @@
+/// Extended Euclid: `(g, x, y)` such that `a * x + b * y == g`, `g` being
+/// the non-negative gcd of `a` and `b`.
+///
+/// Panics on `i64::MIN`, whose gcd with `0` or itself does not fit in an
+/// `i64`. [`mod_inverse`] and [`crt`] only ever pass reduced values.
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    assert!(
+        a != i64::MIN && b != i64::MIN,
+        "egcd({}, {}) overflows",
+        a,
+        b
+    );
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = egcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/// `x` in `0..modulus` with `a * x ≡ 1`, if `a` and `modulus` are coprime
+/// and `modulus` is positive.
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    if modulus <= 0 {
+        return None;
+    }
+    let (g, x, _) = egcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Merges `x ≡ a (mod m)` and `x ≡ b (mod n)` into one congruence modulo
+/// `lcm(m, n)`. The moduli need not be coprime.
+fn combine((a, m): (i64, i64), (b, n): (i64, i64)) -> Option<(i64, i64)> {
+    let (g, p, _) = egcd(m, n);
+    if (b - a) % g != 0 {
+        return None;
+    }
+
+    // the products overflow i64 long before the lcm does
+    let lcm = i128::from(m / g) * i128::from(n);
+    let k = (i128::from((b - a) / g) * i128::from(p)).rem_euclid(i128::from(n / g));
+    let x = (i128::from(a) + i128::from(m) * k).rem_euclid(lcm);
+
+    Some((x.try_into().ok()?, lcm.try_into().ok()?))
+}
+
+/// Solves a system of `x ≡ residue (mod modulus)` congruences, returning the
+/// smallest non-negative `x` and the modulus every solution repeats with.
+///
+/// `None` when the congruences contradict each other, which can happen once
+/// moduli share a factor, when a modulus is not positive or when the
+/// combined modulus overflows `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    congruences
+        .iter()
+        .try_fold((0, 1), |solution, &(residue, modulus)| {
+            if modulus <= 0 {
+                return None;
+            }
+            combine(solution, (residue.rem_euclid(modulus), modulus))
+        })
+}
+
+/// Like [`crt`], but `x` may match any of several residues per modulus.
+/// Returns every solution below the combined modulus, sorted, along with
+/// that modulus.
+pub fn crt_residues(congruences: &[(&[i64], i64)]) -> Option<(Vec<i64>, i64)> {
+    let mut solutions = vec![0];
+    let mut lcm = 1;
+
+    for &(residues, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let combined: Vec<_> = solutions
+            .iter()
+            .flat_map(|&solution| {
+                residues.iter().filter_map(move |&residue| {
+                    combine((solution, lcm), (residue.rem_euclid(modulus), modulus))
+                })
+            })
+            .collect();
+        if combined.is_empty() {
+            return None;
+        }
+
+        lcm = combined[0].1;
+        solutions = combined.into_iter().map(|(solution, _)| solution).collect();
+        solutions.sort_unstable();
+        solutions.dedup();
+    }
+
+    Some((solutions, lcm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_gcd() {
+        for a in -30..30 {
+            for b in -30..30 {
+                let (g, x, y) = egcd(a, b);
+                assert_eq!(a * x + b * y, g, "egcd({}, {})", a, b);
+                assert_eq!(g, num::integer::gcd(a, b), "egcd({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows")]
+    fn extended_gcd_of_min() {
+        egcd(i64::MIN, 0);
+    }
+
+    #[test]
+    fn inverses() {
+        for modulus in 1..30 {
+            for a in -30..30 {
+                let expected = (0..modulus).find(|x| (a * x - 1_i64).rem_euclid(modulus) == 0);
+                assert_eq!(mod_inverse(a, modulus), expected, "{} mod {}", a, modulus);
+            }
+        }
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(-1, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(2, 3), (0, 0)]), None);
+        assert_eq!(crt(&[(2, -3)]), None);
+        assert_eq!(crt_residues(&[(&[1], 0)]), None);
+        assert_eq!(crt_residues(&[(&[1], 4), (&[2], -5)]), None);
+        assert_eq!(mod_inverse(3, 0), None);
+        assert_eq!(mod_inverse(3, -7), None);
+        assert_eq!(mod_inverse(i64::MIN, 7), Some(6));
+
+        let big: i64 = 1 << 31;
+        assert_eq!(crt(&[(0, big), (1, big - 1)]), Some((big, big * (big - 1))));
+        assert_eq!(crt(&[(0, big * big), (1, big * big - 1)]), None);
+    }
+
+    #[test]
+    fn chinese_remainder_with_several_residues() {
+        let systems: [&[(&[i64], i64)]; 4] = [
+            &[(&[1, 2], 4), (&[0, 3], 6)],
+            &[(&[1], 4), (&[2], 6)],
+            &[(&[0, 5], 10), (&[3], 9), (&[1, 2, 3], 4)],
+            &[(&[7, 2], 3), (&[], 5)],
+        ];
+
+        for system in systems {
+            let lcm = system
+                .iter()
+                .fold(1, |acc, &(_, modulus)| num::integer::lcm(acc, modulus));
+            let expected: Vec<_> = (0..lcm)
+                .filter(|x| {
+                    system.iter().all(|&(residues, modulus)| {
+                        residues
+                            .iter()
+                            .any(|residue| (x - residue).rem_euclid(modulus) == 0)
+                    })
+                })
+                .collect();
+
+            let result = crt_residues(system);
+
+            if expected.is_empty() {
+                assert_eq!(result, None, "{:?}", system);
+            } else {
+                assert_eq!(result, Some((expected, lcm)), "{:?}", system);
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::env;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+pub mod cycle;
+mod grid;
+pub mod image;
+mod interval;
+pub mod lines;
+pub mod matcher;
+pub mod math;
+pub mod parse;
+mod point;
+pub mod render;
+pub mod search;
+
+pub use grid::{Grid, Position};
+pub use interval::{IntervalSet, Shift};
+pub use parse::{blocks, parse_header, parse_key_values, parse_lines, parse_number, parse_record};
+pub use point::{Direction, Point};
+
+/// Reads a whole file, see [`lines::LineReader`] for inputs too big for that.
+pub fn open_file<P: AsRef<Path>>(file_path: P) -> std::io::Result<String> {
+    let mut file = File::open(file_path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// Directory holding one `inputs/<day>` folder per day.
+///
+/// `AOC_INPUTS` wins when set, otherwise the folder next to `Cargo.toml` is
+/// used so the binary works from any working directory.
+pub fn inputs_dir() -> PathBuf {
+    env::var_os("AOC_INPUTS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs"))
+}
+
+pub fn day_file(day: u8, file_name: &str) -> PathBuf {
+    inputs_dir().join(day.to_string()).join(file_name)
+}
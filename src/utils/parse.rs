@@ -0,0 +1,211 @@
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Parses `token`, a slice of `line`, reporting its position when it is not
+/// a valid number.
+pub fn parse_number<T: FromStr>(line: &str, token: &str) -> Result<T> {
+    token
+        .parse::<T>()
+        .map_err(|_| Error::parse(line, token, "invalid number"))
+}
+
+/// Applies `parse` to every line, tagging errors with the line they came from.
+pub fn parse_lines<T, F>(input: &str, parse: F) -> Result<Vec<T>>
+where
+    F: Fn(&str) -> Result<T>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse(line).map_err(|e| e.on_line(index)))
+        .collect()
+}
+
+/// Reads the subset of TOML our small config files need: `key = value` lines
+/// where the value is a quoted string or a bare integer, plus `#` comments.
+///
+/// Yields the zero based line index along with every key and value.
+pub fn parse_key_values(input: &str) -> std::result::Result<Vec<(usize, &str, &str)>, String> {
+    let mut pairs = vec![];
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected `key = value`", index + 1))?;
+        let value = value.trim();
+        let value = match value.strip_prefix('"') {
+            Some(quoted) => quoted
+                .strip_suffix('"')
+                .ok_or_else(|| format!("line {}: unterminated string", index + 1))?,
+            None if value.parse::<i64>().is_ok() => value,
+            None => return Err(format!("line {}: invalid value `{}`", index + 1, value)),
+        };
+
+        pairs.push((index, key.trim(), value));
+    }
+
+    Ok(pairs)
+}
+
+/// Every run of digits in `text`, a slice of `line`, everything else being
+/// a separator. A `-` right before the digits is a sign unless it follows
+/// another digit, so `x=-2` holds `-2` but `1-3` holds `1` and `3`.
+/// Numbers that do not fit in `T` are reported as invalid.
+///
+/// Meant for free-form lines, whitespace separated tables should go through
+/// [`parse_number`] so a typo like `4O` is an error rather than `4`.
+pub fn integers<T: FromStr>(line: &str, text: &str) -> Result<Vec<T>> {
+    let bytes = text.as_bytes();
+    let mut numbers = vec![];
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if !bytes[index].is_ascii_digit() {
+            index += 1;
+            continue;
+        }
+        let mut start = index;
+        if start > 0
+            && bytes[start - 1] == b'-'
+            && (start < 2 || !bytes[start - 2].is_ascii_digit())
+        {
+            start -= 1;
+        }
+        while index < bytes.len() && bytes[index].is_ascii_digit() {
+            index += 1;
+        }
+        numbers.push(parse_number(line, &text[start..index])?);
+    }
+
+    Ok(numbers)
+}
+
+/// Splits `input` on blank lines, yielding each block with the zero based
+/// index of its first line.
+pub fn blocks(input: &str) -> Vec<(usize, &str)> {
+    let mut blocks = vec![];
+    // line index and byte offset of the block being read
+    let mut start = None;
+    let mut offset = 0;
+
+    for (index, line) in input.split_inclusive('\n').enumerate() {
+        match (line.trim().is_empty(), start) {
+            (false, None) => start = Some((index, offset)),
+            (true, Some((first, from))) => {
+                blocks.push((first, input[from..offset].trim_end()));
+                start = None;
+            }
+            _ => (),
+        }
+        offset += line.len();
+    }
+    if let Some((first, from)) = start {
+        blocks.push((first, input[from..].trim_end()));
+    }
+
+    blocks
+}
+
+/// Reads a `Label N: rest` line, returning `N` and the text after the colon.
+pub fn parse_header<'a, T: FromStr>(line: &'a str, label: &str) -> Result<(T, &'a str)> {
+    let (header, rest) = line
+        .split_once(':')
+        .ok_or_else(|| Error::parse(line, &line[line.len()..], "expected `:`"))?;
+    let number = header
+        .strip_prefix(label)
+        .filter(|number| number.starts_with(char::is_whitespace))
+        .ok_or_else(|| Error::parse(line, header, format!("expected `{} <number>`", label)))?;
+
+    Ok((parse_number(line, number.trim())?, rest))
+}
+
+/// Reads a `name = (left, right)` line.
+pub fn parse_record(line: &str) -> Result<(&str, &str, &str)> {
+    let (name, children) = line
+        .split_once(" = ")
+        .ok_or_else(|| Error::parse(line, &line[line.len()..], "expected ` = `"))?;
+    let (left, right) = children
+        .strip_prefix('(')
+        .and_then(|children| children.strip_suffix(')'))
+        .and_then(|children| children.split_once(", "))
+        .ok_or_else(|| Error::parse(line, children, "expected `(left, right)`"))?;
+
+    Ok((name, left, right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_integers() {
+        let line = "Sensor at x=-2, y=15: beacon 3";
+        assert_eq!(integers::<i64>(line, line).unwrap(), vec![-2, 15, 3]);
+        assert_eq!(integers::<i64>(line, &line[16..]).unwrap(), vec![15, 3]);
+        assert_eq!(integers::<u32>("a-b map:", "a-b map:").unwrap(), vec![]);
+        assert!(integers::<u8>("300", "300").is_err());
+        assert_eq!(integers::<i32>("1-3 a", "1-3 a").unwrap(), vec![1, 3]);
+        assert_eq!(integers::<i32>("10-20", "10-20").unwrap(), vec![10, 20]);
+        assert_eq!(integers::<i32>("3x4", "3x4").unwrap(), vec![3, 4]);
+        assert_eq!(integers::<i32>("--5 a-6", "--5 a-6").unwrap(), vec![-5, -6]);
+
+        let line = "Distance:  9  400  2";
+        let Err(Error::Parse(error)) = integers::<u8>(line, &line[9..]) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.column, error.text.as_str()), (15, "400"));
+    }
+
+    #[test]
+    fn split_blocks() {
+        let input = "seeds: 1 2\n\na map:\n1 2 3\n4 5 6\n \n\nb map:\n7 8 9\n";
+
+        assert_eq!(
+            blocks(input),
+            vec![
+                (0, "seeds: 1 2"),
+                (2, "a map:\n1 2 3\n4 5 6"),
+                (7, "b map:\n7 8 9")
+            ]
+        );
+        assert_eq!(blocks("\n\n"), vec![]);
+        assert_eq!(blocks("a\r\n\r\nb"), vec![(0, "a"), (2, "b")]);
+    }
+
+    #[test]
+    fn headers() {
+        assert_eq!(
+            parse_header::<u32>("Card   12: 1 2 | 3", "Card").unwrap(),
+            (12, " 1 2 | 3")
+        );
+
+        let Err(Error::Parse(error)) = parse_header::<u32>("Game 1 3 blue", "Game") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.message, "expected `:`");
+        let Err(Error::Parse(error)) = parse_header::<u32>("Gamer 1: 3 blue", "Game") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.message, "expected `Game <number>`");
+        let Err(Error::Parse(error)) = parse_header::<u32>("Game x: 3 blue", "Game") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.column, error.text.as_str()), (6, "x"));
+    }
+
+    #[test]
+    fn records() {
+        assert_eq!(
+            parse_record("AAA = (BBB, CCC)").unwrap(),
+            ("AAA", "BBB", "CCC")
+        );
+        assert!(parse_record("AAA (BBB, CCC)").is_err());
+        assert!(parse_record("AAA = BBB, CCC").is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use super::Position;
+
+/// A signed `(row, column)` coordinate, free to leave any grid. Convert to a
+/// [`Position`] to index one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl Point {
+    pub const fn new(row: i64, col: i64) -> Self {
+        Self { row, col }
+    }
+
+    /// `None` when either coordinate is negative.
+    pub fn to_position(self) -> Option<Position> {
+        Some((
+            usize::try_from(self.row).ok()?,
+            usize::try_from(self.col).ok()?,
+        ))
+    }
+
+    pub fn manhattan(self, other: Self) -> u64 {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+}
+
+impl From<Position> for Point {
+    fn from((row, col): Position) -> Self {
+        Self::new(row as i64, col as i64)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.row, -self.col)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Self;
+
+    fn mul(self, factor: i64) -> Self {
+        Self::new(self.row * factor, self.col * factor)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+/// The four compass directions, north being the top row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Clockwise, starting with north.
+    pub const ALL: [Direction; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn offset(self) -> Point {
+        match self {
+            Self::North => Point::new(-1, 0),
+            Self::East => Point::new(0, 1),
+            Self::South => Point::new(1, 0),
+            Self::West => Point::new(0, -1),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::North => Self::West,
+            Self::East => Self::North,
+            Self::South => Self::East,
+            Self::West => Self::South,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Self;
+
+    fn add(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_arithmetic() {
+        let mut point = Point::new(2, 3);
+
+        point += Point::new(-4, 1);
+        point -= Point::new(0, 2);
+
+        assert_eq!(point, Point::new(-2, 2));
+        assert_eq!(-point, Point::new(2, -2));
+        assert_eq!(point * 3, Point::new(-6, 6));
+        assert_eq!(point.manhattan(Point::new(1, 1)), 4);
+        assert_eq!(point.to_position(), None);
+        assert_eq!(Point::from((4, 5)).to_position(), Some((4, 5)));
+    }
+
+    #[test]
+    fn directions() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.reverse().reverse(), direction);
+            assert_eq!(
+                direction.offset() + direction.reverse().offset(),
+                Point::default()
+            );
+        }
+
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::East.reverse(), Direction::West);
+        assert_eq!(Point::new(1, 1) + Direction::South, Point::new(2, 1));
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+use super::{Grid, Position};
+
+/// The eight standard ANSI colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn code(self) -> u8 {
+        match self {
+            Self::Black => 0,
+            Self::Red => 1,
+            Self::Green => 2,
+            Self::Yellow => 3,
+            Self::Blue => 4,
+            Self::Magenta => 5,
+            Self::Cyan => 6,
+            Self::White => 7,
+        }
+    }
+}
+
+/// How a cell is drawn, `None` fields keep whatever is below them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub fn fg(color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            ..Self::default()
+        }
+    }
+
+    pub fn bg(color: Color) -> Self {
+        Self {
+            bg: Some(color),
+            ..Self::default()
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// `self` drawn over `below`.
+    fn over(self, below: Self) -> Self {
+        Self {
+            fg: self.fg.or(below.fg),
+            bg: self.bg.or(below.bg),
+            bold: self.bold || below.bold,
+        }
+    }
+
+    fn escape(self) -> String {
+        let mut codes = vec![];
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if let Some(fg) = self.fg {
+            codes.push((30 + fg.code()).to_string());
+        }
+        if let Some(bg) = self.bg {
+            codes.push((40 + bg.code()).to_string());
+        }
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+/// Whether stdout should get colours: it has to be a terminal and
+/// `NO_COLOR` must not be set.
+pub fn colors_enabled() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+/// A grid turned into glyphs and styles, ready to print. Highlight layers
+/// are drawn over the base cells in the order they are added.
+///
+/// `Display` only emits colours when [`colors_enabled`] says so, use
+/// [`Canvas::render`] to choose.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    cells: Grid<(String, Style)>,
+}
+
+impl Canvas {
+    pub fn new<T, F>(grid: &Grid<T>, mut draw: F) -> Self
+    where
+        F: FnMut(&T) -> (String, Style),
+    {
+        Self {
+            cells: grid.map(|cell| draw(cell)),
+        }
+    }
+
+    /// Draws `style` over every cell in `positions` inside the canvas.
+    pub fn layer<I>(mut self, positions: I, style: Style) -> Self
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        let positions: HashSet<_> = positions.into_iter().collect();
+        for position in positions {
+            if let Some((_, below)) = self.cells.get_mut(position) {
+                *below = style.over(*below);
+            }
+        }
+        self
+    }
+
+    pub fn render(&self, colors: bool) -> String {
+        let mut output = String::new();
+
+        for (index, row) in self.cells.rows().enumerate() {
+            if index > 0 {
+                output.push('\n');
+            }
+            let mut current = Style::default();
+            for (glyph, style) in row {
+                if colors && *style != current {
+                    output.push_str("\x1b[0m");
+                    if *style != Style::default() {
+                        output.push_str(&style.escape());
+                    }
+                    current = *style;
+                }
+                output.push_str(glyph);
+            }
+            if colors && current != Style::default() {
+                output.push_str("\x1b[0m");
+            }
+        }
+
+        output
+    }
+}
+
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(colors_enabled()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> Canvas {
+        let grid = Grid::parse("#.\n.#", |c| Some(c == '#')).unwrap();
+        Canvas::new(&grid, |&wall| {
+            if wall {
+                ("#".to_string(), Style::fg(Color::White).bold())
+            } else {
+                (".".to_string(), Style::default())
+            }
+        })
+    }
+
+    #[test]
+    fn render_plain() {
+        let canvas = canvas().layer([(0, 1)], Style::bg(Color::Red));
+
+        assert_eq!(canvas.render(false), "#.\n.#");
+    }
+
+    #[test]
+    fn render_layers() {
+        let canvas = canvas()
+            .layer([(0, 0), (0, 1), (5, 5)], Style::bg(Color::Blue))
+            .layer([(0, 1)], Style::fg(Color::Green));
+
+        assert_eq!(
+            canvas.render(true),
+            "\x1b[0m\x1b[1;37;44m#\x1b[0m\x1b[32;44m.\x1b[0m\n\
+             .\x1b[0m\x1b[1;37m#\x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Result of exploring a graph from a start node: the cost of the cheapest
+/// known route to every reached node and the node it was reached from.
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    pub distances: HashMap<N, u64>,
+    pub predecessors: HashMap<N, N>,
+}
+
+impl<N> Search<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn new(start: N) -> Self {
+        Self {
+            distances: HashMap::from([(start, 0)]),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    /// The node furthest from the start, ties broken arbitrarily.
+    pub fn farthest(&self) -> Option<(&N, u64)> {
+        self.distances
+            .iter()
+            .max_by_key(|&(_, &distance)| distance)
+            .map(|(node, &distance)| (node, distance))
+    }
+
+    /// Nodes from the start to `target`, both included.
+    pub fn path(&self, target: &N) -> Option<Vec<N>> {
+        self.distances.get(target)?;
+
+        let mut path = vec![target.clone()];
+        while let Some(previous) = self.predecessors.get(path.last()?) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Breadth first search, every edge costs one.
+pub fn bfs<N, F, I>(start: N, mut neighbours: F) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, distance)) = queue.pop_front() {
+        for next in neighbours(&node) {
+            if search.distances.contains_key(&next) {
+                continue;
+            }
+            search.distances.insert(next.clone(), distance + 1);
+            search.predecessors.insert(next.clone(), node.clone());
+            queue.push_back((next, distance + 1));
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's shortest paths, `neighbours` yields each next node with the
+/// cost of the edge leading to it.
+pub fn dijkstra<N, F, I>(start: N, neighbours: F) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    explore(start, neighbours, |_| 0, |_| false).0
+}
+
+/// A* search towards the first node accepted by `is_goal`. `heuristic` must
+/// never overestimate the remaining cost, or the path may not be the
+/// cheapest.
+///
+/// Returns the path, start and goal included, with its cost.
+pub fn astar<N, F, I, H, G>(
+    start: N,
+    neighbours: F,
+    heuristic: H,
+    is_goal: G,
+) -> Option<(Vec<N>, u64)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let (search, goal) = explore(start, neighbours, heuristic, is_goal);
+    let goal = goal?;
+    let cost = search.distance(&goal)?;
+
+    Some((search.path(&goal)?, cost))
+}
+
+/// Shared by Dijkstra, which has no heuristic nor goal, and A*.
+fn explore<N, F, I, H, G>(
+    start: N,
+    mut neighbours: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> (Search<N>, Option<N>)
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new(start.clone());
+    // the heap only holds indices into `nodes`, so nodes need not be `Ord`
+    let mut nodes = vec![start.clone()];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+
+    while let Some(Reverse((_, distance, index))) = heap.pop() {
+        let node = nodes[index].clone();
+        if search.distance(&node).is_some_and(|best| best < distance) {
+            continue;
+        }
+        if is_goal(&node) {
+            return (search, Some(node));
+        }
+
+        for (next, cost) in neighbours(&node) {
+            let distance = distance + cost;
+            if search.distance(&next).is_some_and(|best| best <= distance) {
+                continue;
+            }
+            search.distances.insert(next.clone(), distance);
+            search.predecessors.insert(next.clone(), node.clone());
+            heap.push(Reverse((
+                distance + heuristic(&next),
+                distance,
+                nodes.len(),
+            )));
+            nodes.push(next);
+        }
+    }
+
+    (search, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{Grid, Point, Position};
+
+    const MAZE: &str = "..#...
+.##.#.
+....#.
+.#..#.";
+
+    fn maze() -> Grid<bool> {
+        Grid::parse(MAZE, |c| Some(c == '.')).unwrap()
+    }
+
+    fn open_neighbours(maze: &Grid<bool>, position: Position) -> Vec<Position> {
+        maze.neighbours4(position)
+            .filter(|&next| maze[next])
+            .collect()
+    }
+
+    #[test]
+    fn breadth_first() {
+        let maze = maze();
+
+        let search = bfs((0, 0), |&position| open_neighbours(&maze, position));
+
+        assert_eq!(search.distance(&(0, 0)), Some(0));
+        assert_eq!(search.distance(&(0, 5)), Some(9));
+        assert_eq!(search.distance(&(1, 2)), None);
+        assert_eq!(search.farthest(), Some((&(3, 5), 12)));
+        assert_eq!(
+            search.path(&(2, 3)),
+            Some(vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (2, 3)])
+        );
+        assert_eq!(search.path(&(1, 2)), None);
+    }
+
+    #[test]
+    fn weighted_paths() {
+        // a -1-> b -1-> c is cheaper than the direct a -5-> c
+        let edges = HashMap::from([
+            ('a', vec![('b', 1), ('c', 5)]),
+            ('b', vec![('c', 1), ('d', 7)]),
+            ('c', vec![('d', 2)]),
+        ]);
+        let neighbours = |node: &char| edges.get(node).cloned().unwrap_or_default();
+
+        let search = dijkstra('a', neighbours);
+        let path = astar('a', neighbours, |_| 0, |&node| node == 'd');
+
+        assert_eq!(search.distance(&'c'), Some(2));
+        assert_eq!(search.distance(&'d'), Some(4));
+        assert_eq!(search.path(&'d'), Some(vec!['a', 'b', 'c', 'd']));
+        assert_eq!(path, Some((vec!['a', 'b', 'c', 'd'], 4)));
+        assert_eq!(astar('d', neighbours, |_| 0, |&node| node == 'a'), None);
+    }
+
+    #[test]
+    fn astar_agrees_with_bfs() {
+        let maze = maze();
+        let goal = (3, 5);
+
+        let search = bfs((0, 0), |&position| open_neighbours(&maze, position));
+        let (path, cost) = astar(
+            (0, 0),
+            |&position| {
+                open_neighbours(&maze, position)
+                    .into_iter()
+                    .map(|next| (next, 1))
+            },
+            |&position| Point::from(position).manhattan(Point::from(goal)),
+            |&position| position == goal,
+        )
+        .unwrap();
+
+        assert_eq!(Some(cost), search.distance(&goal));
+        assert_eq!(path.len() as u64, cost + 1);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&goal));
+    }
+}